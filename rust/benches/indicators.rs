@@ -0,0 +1,115 @@
+//! Indicator and candle conversion benchmarks over a year of 1m candles.
+//!
+//! Run with `cargo bench`. Each allocating API is measured next to its `_into` variant
+//! reusing a buffer.
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use exchange_outpost_abi::Candle;
+use exchange_outpost_abi::indicators::{Atr, Ema, Indicator, Mfi, Obv, RollingExt, Rsi};
+use exchange_outpost_abi::testgen::{CandleGenerator, PriceModel};
+
+const MINUTES_PER_YEAR: usize = 365 * 24 * 60;
+
+fn year_of_candles() -> Vec<Candle<f64>> {
+    CandleGenerator::new(PriceModel::Gbm)
+        .with_timing(0, 60_000)
+        .generate(MINUTES_PER_YEAR)
+}
+
+fn streaming(c: &mut Criterion) {
+    let candles = year_of_candles();
+    let closes: Vec<f64> = candles.iter().map(|c| c.close).collect();
+    let mut out = Vec::with_capacity(candles.len());
+
+    c.bench_function("ema compute", |b| {
+        b.iter(|| black_box(Ema::new(20).compute(&candles)))
+    });
+    c.bench_function("ema compute_into", |b| {
+        b.iter(|| {
+            Ema::new(20).compute_into(&candles, &mut out);
+            black_box(&out);
+        })
+    });
+    c.bench_function("ema update_values_into", |b| {
+        b.iter(|| {
+            Ema::new(20).update_values_into(&closes, &mut out);
+            black_box(&out);
+        })
+    });
+    c.bench_function("rsi compute", |b| {
+        b.iter(|| black_box(Rsi::new(14).compute(&candles)))
+    });
+    c.bench_function("rsi compute_into", |b| {
+        b.iter(|| {
+            Rsi::new(14).compute_into(&candles, &mut out);
+            black_box(&out);
+        })
+    });
+    c.bench_function("atr compute", |b| {
+        b.iter(|| black_box(Atr::new(14).compute(&candles)))
+    });
+    c.bench_function("atr compute_into", |b| {
+        b.iter(|| {
+            Atr::new(14).compute_into(&candles, &mut out);
+            black_box(&out);
+        })
+    });
+    c.bench_function("obv compute_into", |b| {
+        b.iter(|| {
+            Obv::new().compute_into(&candles, &mut out);
+            black_box(&out);
+        })
+    });
+    let mut mfi = Mfi::new(14);
+    c.bench_function("mfi compute_into", |b| {
+        b.iter(|| {
+            mfi.reset();
+            mfi.compute_into(&candles, &mut out);
+            black_box(&out);
+        })
+    });
+}
+
+fn rolling(c: &mut Criterion) {
+    let closes: Vec<f64> = year_of_candles().iter().map(|c| c.close).collect();
+    let mut out = Vec::with_capacity(closes.len());
+
+    c.bench_function("rolling mean", |b| {
+        b.iter(|| black_box(closes.rolling(50).mean()))
+    });
+    c.bench_function("rolling mean_into", |b| {
+        b.iter(|| {
+            closes.rolling(50).mean_into(&mut out);
+            black_box(&out);
+        })
+    });
+    c.bench_function("rolling max", |b| {
+        b.iter(|| black_box(closes.rolling(50).max()))
+    });
+    c.bench_function("rolling max_into", |b| {
+        b.iter(|| {
+            closes.rolling(50).max_into(&mut out);
+            black_box(&out);
+        })
+    });
+}
+
+fn conversion(c: &mut Criterion) {
+    let candles = year_of_candles();
+    let mut out = Vec::with_capacity(candles.len());
+
+    c.bench_function("to_decimal collect", |b| {
+        b.iter(|| black_box(candles.iter().map(|c| c.to_decimal(2)).collect::<Vec<_>>()))
+    });
+    c.bench_function("to_decimal into", |b| {
+        b.iter(|| {
+            out.clear();
+            out.extend(candles.iter().map(|c| c.to_decimal(2)));
+            black_box(&out);
+        })
+    });
+}
+
+criterion_group!(benches, streaming, rolling, conversion);
+criterion_main!(benches);
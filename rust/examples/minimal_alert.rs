@@ -0,0 +1,25 @@
+//! A price alert built with the `minimal` feature set, the smallest kind of plugin.
+//!
+//! ```sh
+//! cargo build --release --target wasm32-unknown-unknown --example minimal_alert \
+//!     --no-default-features --features minimal
+//! ```
+use exchange_outpost_abi::{FunctionArgs, schedule_webhook};
+use extism_pdk::{FnResult, Json, plugin_fn};
+
+#[plugin_fn]
+pub fn run(args: FunctionArgs) -> FnResult<Json<bool>> {
+    let threshold: f64 = args.get_call_argument("threshold")?;
+    let url: String = args.get_call_argument("url")?;
+    let mut triggered = false;
+    for label in args.get_labels() {
+        let Some(last) = args.get_candles(label)?.last() else {
+            continue;
+        };
+        if last.close > threshold {
+            schedule_webhook(&url, &format!("{} closed at {}", label, last.close))?;
+            triggered = true;
+        }
+    }
+    Ok(Json(triggered))
+}
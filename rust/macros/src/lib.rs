@@ -0,0 +1,93 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, ItemFn, LitStr, parse_macro_input, spanned::Spanned};
+
+/// Exports a function as a plugin entry point.
+///
+/// The function takes the `FunctionArgs` sent by the host and returns its output, any
+/// serializable type, or an `AbiError` whose return code is reported to the host:
+///
+/// ```ignore
+/// #[exchange_function]
+/// pub fn run(args: FunctionArgs) -> Result<Signal, AbiError> {
+///     let candles = args.get_candles("BTCUSDT")?;
+///     Ok(Signal { buy: candles.len() > 10 })
+/// }
+/// ```
+///
+/// The function itself is left untouched so it can be called from native tests. The
+/// export, which parses the input and serializes the output, is only generated for
+/// wasm targets.
+#[proc_macro_attribute]
+pub fn exchange_function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let function = parse_macro_input!(item as ItemFn);
+    if !attr.is_empty() {
+        let attr = proc_macro2::TokenStream::from(attr);
+        return syn::Error::new(attr.span(), "exchange_function takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+    if function.sig.inputs.len() != 1 {
+        return syn::Error::new(
+            function.sig.inputs.span(),
+            "exchange_function expects a single FunctionArgs argument",
+        )
+        .to_compile_error()
+        .into();
+    }
+    if function.sig.ident == "main" {
+        return syn::Error::new(
+            function.sig.ident.span(),
+            "exchange_function cannot export `main`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let name = &function.sig.ident;
+    let export_name = name.to_string();
+    let wrapper = format_ident!("__exchange_function_{}", name);
+    quote! {
+        #function
+
+        #[cfg(target_arch = "wasm32")]
+        #[doc(hidden)]
+        #[unsafe(export_name = #export_name)]
+        pub extern "C" fn #wrapper() -> i32 {
+            ::exchange_outpost_abi::__private::export(#name)
+        }
+    }
+    .into()
+}
+
+/// Implements `PipeOutput` for a type that is also `Serialize` and `Deserialize`.
+///
+/// The content type defaults to the name of the type and can be set with
+/// `#[pipe(content_type = "...")]`, e.g. to version the format.
+#[proc_macro_derive(PipeOutput, attributes(pipe))]
+pub fn derive_pipe_output(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let mut content_type = input.ident.to_string();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("pipe")) {
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("content_type") {
+                content_type = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("expected `content_type`"))
+            }
+        });
+        if let Err(e) = parsed {
+            return e.to_compile_error().into();
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::exchange_outpost_abi::PipeOutput for #name #ty_generics #where_clause {
+            const CONTENT_TYPE: &'static str = #content_type;
+        }
+    }
+    .into()
+}
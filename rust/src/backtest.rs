@@ -0,0 +1,312 @@
+//! Vectorized backtesting of target-position signals over candles.
+//!
+//! A signal is the target exposure as a fraction of equity for each candle: `1.0` is
+//! fully long, `-1.0` fully short and `0.0` flat. The signal of a candle is only known
+//! once it closes, so it is executed at the open of the next candle. The position is only
+//! resized when the signal changes, so a constant signal does not rebalance on every candle.
+use extism_pdk::{Error, WithReturnCode};
+use serde::Serialize;
+
+use crate::stats::PerformanceStats;
+use crate::{Candle, FunctionOutput};
+
+/// Execution costs and reporting settings of a backtest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BacktestConfig {
+    pub initial_capital: f64,
+    /// Fee charged on every fill, as a fraction of its notional.
+    pub fee_rate: f64,
+    /// Price slippage of every fill, as a fraction of the open price, always against the trade.
+    pub slippage: f64,
+    /// Number of candles in a year, used to annualize the summary statistics.
+    pub periods_per_year: f64,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        BacktestConfig {
+            initial_capital: 10_000.0,
+            fee_rate: 0.001,
+            slippage: 0.0,
+            periods_per_year: 365.0,
+        }
+    }
+}
+
+/// An executed order.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Fill {
+    pub timestamp: i64,
+    pub price: f64,
+    /// Signed quantity, positive for buys and negative for sells.
+    pub quantity: f64,
+    pub fee: f64,
+}
+
+/// Direction of a trade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Long,
+    Short,
+}
+
+/// A position from the moment it is opened until it is back to flat.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trade {
+    pub side: Side,
+    pub entry_timestamp: i64,
+    pub exit_timestamp: i64,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    /// Profit net of fees.
+    pub pnl: f64,
+    /// Fees paid on every fill of the trade.
+    pub fees: f64,
+}
+
+/// Outcome of a backtest run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BacktestResult {
+    pub fills: Vec<Fill>,
+    pub trades: Vec<Trade>,
+    /// Equity marked at the close of every candle.
+    pub equity: Vec<(i64, f64)>,
+    pub stats: PerformanceStats,
+    /// Fraction of closed trades with a positive net profit.
+    pub trade_win_rate: Option<f64>,
+    pub total_fees: f64,
+}
+
+impl BacktestResult {
+    /// Wraps the result into the output returned to the host.
+    pub fn to_output(&self) -> Result<FunctionOutput, WithReturnCode<Error>> {
+        FunctionOutput::new(self)
+    }
+}
+
+/// Runs a backtest of a precomputed signal series aligned with `candles`.
+///
+/// Extra signals past the last candle are ignored.
+pub fn run(candles: &[Candle<f64>], signals: &[f64], config: BacktestConfig) -> BacktestResult {
+    run_strategy(candles, config, |i, _| {
+        signals.get(i).copied().unwrap_or(0.0)
+    })
+}
+
+/// Runs a backtest where `strategy` returns the target exposure after seeing each candle.
+///
+/// The closure receives the candle index and the closed candle, so it can feed an
+/// [`Indicator`](crate::indicators::Indicator) and map its value to a position.
+///
+/// Candles with a non-positive open are not traded; the latest target is then applied on
+/// the next tradable candle.
+pub fn run_strategy<F>(
+    candles: &[Candle<f64>],
+    config: BacktestConfig,
+    mut strategy: F,
+) -> BacktestResult
+where
+    F: FnMut(usize, &Candle<f64>) -> f64,
+{
+    let mut engine = Engine {
+        config,
+        cash: config.initial_capital,
+        position: 0.0,
+        fills: Vec::new(),
+        trades: Vec::new(),
+        open_trade: None,
+    };
+    let mut equity = Vec::with_capacity(candles.len());
+    let mut current = 0.0;
+    let mut target: Option<f64> = None;
+    for (i, candle) in candles.iter().enumerate() {
+        if let Some(target) = target.take().filter(|&target| target != current)
+            && engine.rebalance(candle, target)
+        {
+            current = target;
+        }
+        equity.push((
+            candle.timestamp,
+            engine.cash + engine.position * candle.close,
+        ));
+        target = Some(strategy(i, candle));
+    }
+
+    let equity_values: Vec<f64> = equity.iter().map(|&(_, value)| value).collect();
+    let closed: Vec<f64> = engine.trades.iter().map(|t| t.pnl).collect();
+    BacktestResult {
+        stats: PerformanceStats::from_equity(&equity_values, config.periods_per_year),
+        trade_win_rate: crate::stats::win_rate(&closed),
+        total_fees: engine.fills.iter().map(|f| f.fee).sum(),
+        fills: engine.fills,
+        trades: engine.trades,
+        equity,
+    }
+}
+
+struct OpenTrade {
+    side: Side,
+    entry_timestamp: i64,
+    entry_price: f64,
+    // Net cash received by the fills of the trade, fees included
+    cash_flow: f64,
+    fees: f64,
+}
+
+struct Engine {
+    config: BacktestConfig,
+    cash: f64,
+    position: f64,
+    fills: Vec<Fill>,
+    trades: Vec<Trade>,
+    open_trade: Option<OpenTrade>,
+}
+
+impl Engine {
+    // Returns false if the candle cannot be traded, leaving the position unchanged
+    fn rebalance(&mut self, candle: &Candle<f64>, target: f64) -> bool {
+        if candle.open <= 0.0 || !target.is_finite() {
+            return false;
+        }
+        let equity = self.cash + self.position * candle.open;
+        let target_position = target * equity.max(0.0) / candle.open;
+        // Reversals go through flat so the closed trade is reported on its own
+        if self.position != 0.0 && target_position.signum() != self.position.signum() {
+            self.fill(candle, -self.position);
+        }
+        let delta = target_position - self.position;
+        if delta.abs() > f64::EPSILON * target_position.abs().max(1.0) {
+            self.fill(candle, delta);
+        }
+        true
+    }
+
+    fn fill(&mut self, candle: &Candle<f64>, quantity: f64) {
+        let price = candle.open * (1.0 + self.config.slippage * quantity.signum());
+        let fee = (quantity * price).abs() * self.config.fee_rate;
+        let cash_flow = -quantity * price - fee;
+        self.cash += cash_flow;
+        self.position += quantity;
+        self.fills.push(Fill {
+            timestamp: candle.timestamp,
+            price,
+            quantity,
+            fee,
+        });
+
+        let trade = self.open_trade.get_or_insert(OpenTrade {
+            side: if quantity > 0.0 {
+                Side::Long
+            } else {
+                Side::Short
+            },
+            entry_timestamp: candle.timestamp,
+            entry_price: price,
+            cash_flow: 0.0,
+            fees: 0.0,
+        });
+        trade.cash_flow += cash_flow;
+        trade.fees += fee;
+        if self.position.abs() <= f64::EPSILON * quantity.abs() {
+            self.position = 0.0;
+            let trade = self.open_trade.take().expect("trade is open");
+            self.trades.push(Trade {
+                side: trade.side,
+                entry_timestamp: trade.entry_timestamp,
+                exit_timestamp: candle.timestamp,
+                entry_price: trade.entry_price,
+                exit_price: price,
+                pnl: trade.cash_flow,
+                fees: trade.fees,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candles(opens: &[f64]) -> Vec<Candle<f64>> {
+        opens
+            .iter()
+            .enumerate()
+            .map(|(i, &open)| Candle {
+                timestamp: i as i64,
+                open,
+                high: open,
+                low: open,
+                close: open,
+                volume: 1.0,
+            })
+            .collect()
+    }
+
+    fn no_costs() -> BacktestConfig {
+        BacktestConfig {
+            initial_capital: 1_000.0,
+            fee_rate: 0.0,
+            slippage: 0.0,
+            periods_per_year: 365.0,
+        }
+    }
+
+    #[test]
+    fn test_signal_executes_on_next_open() {
+        let candles = candles(&[100.0, 100.0, 110.0, 121.0]);
+        let result = run(&candles, &[1.0, 1.0, 0.0, 0.0], no_costs());
+        assert_eq!(result.fills.len(), 2);
+        assert_eq!(result.fills[0].timestamp, 1);
+        assert_eq!(result.fills[0].quantity, 10.0);
+        assert_eq!(result.fills[1].timestamp, 3);
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(result.trades[0].side, Side::Long);
+        assert!((result.trades[0].pnl - 210.0).abs() < 1e-9);
+        assert!((result.equity.last().unwrap().1 - 1_210.0).abs() < 1e-9);
+        assert_eq!(result.trade_win_rate, Some(1.0));
+    }
+
+    #[test]
+    fn test_fees_and_slippage() {
+        let candles = candles(&[100.0, 100.0, 100.0]);
+        let config = BacktestConfig {
+            fee_rate: 0.01,
+            slippage: 0.01,
+            ..no_costs()
+        };
+        let result = run(&candles, &[1.0, 0.0, 0.0], config);
+        assert_eq!(result.fills[0].price, 101.0);
+        assert_eq!(result.fills[1].price, 99.0);
+        assert!(result.trades[0].pnl < 0.0);
+        assert!(result.total_fees > 0.0);
+        assert_eq!(result.trade_win_rate, Some(0.0));
+    }
+
+    #[test]
+    fn test_reversal_closes_trade() {
+        let candles = candles(&[100.0, 100.0, 90.0, 80.0]);
+        let result = run_strategy(&candles, no_costs(), |i, _| if i == 0 { 1.0 } else { -1.0 });
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(result.trades[0].side, Side::Long);
+        assert_eq!(result.fills.len(), 3);
+        assert!(result.fills[2].quantity < 0.0);
+    }
+
+    #[test]
+    fn test_zero_open_retries_on_next_candle() {
+        let candles = candles(&[100.0, 0.0, 100.0, 110.0]);
+        let result = run(&candles, &[1.0, 1.0, 1.0, 1.0], no_costs());
+        assert_eq!(result.fills.len(), 1);
+        assert_eq!(result.fills[0].timestamp, 2);
+        assert_eq!(result.fills[0].quantity, 10.0);
+        assert!((result.equity.last().unwrap().1 - 1_100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_to_output() {
+        let result = run(&candles(&[1.0, 2.0]), &[1.0], no_costs());
+        let output = result.to_output().unwrap();
+        assert!(output.as_value().get("equity").is_some());
+    }
+}
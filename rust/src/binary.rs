@@ -0,0 +1,167 @@
+//! Compact binary encoding of candle arrays, for payloads too large to be parsed quickly
+//! as JSON numbers.
+//!
+//! The encoding is a header made of the magic bytes `EOC1` and the candle count as a
+//! little-endian `u32`, followed by six little-endian columns of that many values: the
+//! timestamps in milliseconds as `i64`, then the open, high, low, close and volume as
+//! `f64`. In a JSON payload, the `candles` of a ticker may be sent as a base64 string of
+//! this encoding instead of an array of candles; both forms are always accepted, so the
+//! host can pick one per ticker.
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use extism_pdk::Error;
+use serde::Deserializer;
+use serde::de::{self, SeqAccess, Visitor};
+
+use crate::Candle;
+
+const MAGIC: &[u8; 4] = b"EOC1";
+const HEADER_LEN: usize = 8;
+
+/// Encodes `candles` in the binary encoding.
+pub fn encode_candles(candles: &[Candle<f64>]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + candles.len() * 48);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&(candles.len() as u32).to_le_bytes());
+    for candle in candles {
+        bytes.extend_from_slice(&candle.timestamp.to_le_bytes());
+    }
+    let columns: [fn(&Candle<f64>) -> f64; 5] =
+        [|c| c.open, |c| c.high, |c| c.low, |c| c.close, |c| c.volume];
+    for column in columns {
+        for candle in candles {
+            bytes.extend_from_slice(&column(candle).to_le_bytes());
+        }
+    }
+    bytes
+}
+
+/// Decodes candles from the binary encoding.
+pub fn decode_candles(bytes: &[u8]) -> Result<Vec<Candle<f64>>, Error> {
+    if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+        return Err(Error::msg("not a binary candle array"));
+    }
+    let count = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+    if count
+        .checked_mul(48)
+        .and_then(|len| len.checked_add(HEADER_LEN))
+        != Some(bytes.len())
+    {
+        return Err(Error::msg(format!(
+            "binary candle array of {} candles has {} bytes",
+            count,
+            bytes.len()
+        )));
+    }
+    let column = |index: usize| {
+        bytes[HEADER_LEN + index * count * 8..HEADER_LEN + (index + 1) * count * 8]
+            .chunks_exact(8)
+            .map(|chunk| <[u8; 8]>::try_from(chunk).unwrap_or_default())
+    };
+    let mut candles: Vec<Candle<f64>> = column(0)
+        .map(|timestamp| Candle {
+            timestamp: i64::from_le_bytes(timestamp),
+            open: 0.0,
+            high: 0.0,
+            low: 0.0,
+            close: 0.0,
+            volume: 0.0,
+        })
+        .collect();
+    let fields: [fn(&mut Candle<f64>) -> &mut f64; 5] = [
+        |c| &mut c.open,
+        |c| &mut c.high,
+        |c| &mut c.low,
+        |c| &mut c.close,
+        |c| &mut c.volume,
+    ];
+    for (index, field) in fields.into_iter().enumerate() {
+        for (candle, value) in candles.iter_mut().zip(column(index + 1)) {
+            *field(candle) = f64::from_le_bytes(value);
+        }
+    }
+    Ok(candles)
+}
+
+/// Encodes `candles` as the base64 string accepted in place of a JSON candle array.
+pub fn encode_candles_base64(candles: &[Candle<f64>]) -> String {
+    STANDARD.encode(encode_candles(candles))
+}
+
+/// Deserializes candles sent either as a JSON array or as a base64 binary string.
+pub(crate) fn deserialize_candles<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Candle<f64>>, D::Error> {
+    deserializer.deserialize_any(CandlesVisitor)
+}
+
+struct CandlesVisitor;
+
+impl<'de> Visitor<'de> for CandlesVisitor {
+    type Value = Vec<Candle<f64>>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("an array of candles or a base64 binary candle array")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        let bytes = STANDARD.decode(value).map_err(E::custom)?;
+        decode_candles(&bytes).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut candles = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(candle) = seq.next_element::<Candle<f64>>()? {
+            candles.push(candle);
+        }
+        Ok(candles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TickersData;
+
+    fn candles() -> Vec<Candle<f64>> {
+        (0..3)
+            .map(|i| Candle {
+                timestamp: 1_700_000_000_000 + i * 60_000,
+                open: 1.0 + i as f64,
+                high: 2.0 + i as f64,
+                low: 0.5,
+                close: 1.5,
+                volume: 10.0 * i as f64,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes = encode_candles(&candles());
+        assert_eq!(bytes.len(), 8 + 3 * 48);
+        let decoded = decode_candles(&bytes).unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded[2].timestamp, 1_700_000_120_000);
+        assert_eq!(decoded[2].open, 3.0);
+        assert_eq!(decoded[2].volume, 20.0);
+        assert!(decode_candles(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode_candles(b"JSON").is_err());
+    }
+
+    #[test]
+    fn test_tickers_data_accepts_both_forms() {
+        let json = format!(
+            r#"{{"symbol": "BTCUSDT", "exchange": "binance", "precision": 2, "candles": "{}"}}"#,
+            encode_candles_base64(&candles())
+        );
+        let binary: TickersData = serde_json::from_str(&json).unwrap();
+        let array: TickersData = serde_json::from_str(
+            r#"{"symbol": "BTCUSDT", "exchange": "binance", "precision": 2, "candles": [[1700000000000, 1, 2, 0.5, 1.5, 0]]}"#,
+        )
+        .unwrap();
+        assert_eq!(binary.candles.len(), 3);
+        assert_eq!(binary.candles[0].close, array.candles[0].close);
+        assert_eq!(binary.candles[0].timestamp, array.candles[0].timestamp);
+    }
+}
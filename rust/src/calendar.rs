@@ -0,0 +1,259 @@
+//! Exchange trading calendars, to tell market holidays apart from missing data and to
+//! schedule alerts around the next open.
+//!
+//! Equity exchanges follow the NYSE holiday rules, computed for any year. Early closes are
+//! not modelled. Other exchanges, like crypto venues, trade continuously.
+//!
+//! ```ignore
+//! let today = Date::from_timestamp(now()?, TimeZone::NEW_YORK);
+//! if !is_trading_day("NASDAQ", today) {
+//!     let open = next_open("NASDAQ", now()?);
+//! }
+//! ```
+use std::fmt;
+
+use crate::session::{civil_from_days, days_from_civil, weekday};
+use crate::{Session, TimeZone};
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Calendar date, in the local time of an exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    pub const fn new(year: i32, month: u8, day: u8) -> Self {
+        Date { year, month, day }
+    }
+
+    /// Date at `timestamp` in `time_zone`.
+    pub fn from_timestamp(timestamp: i64, time_zone: TimeZone) -> Self {
+        Date::from_days(time_zone.to_local(timestamp).div_euclid(DAY_MS))
+    }
+
+    fn from_days(days: i64) -> Self {
+        let (year, month, day) = civil_from_days(days);
+        Date::new(year, month, day)
+    }
+
+    fn days(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day)
+    }
+
+    /// Whether the date is a Saturday or a Sunday.
+    pub fn is_weekend(&self) -> bool {
+        weekday(self.days()) >= 5
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Trading calendar of an exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketCalendar {
+    /// Open at all times, like crypto exchanges.
+    Continuous,
+    /// New York Stock Exchange, also used by Nasdaq.
+    Nyse,
+}
+
+impl MarketCalendar {
+    /// Calendar of the exchange named like in [`TickersData::exchange`](crate::TickersData).
+    pub fn for_exchange(exchange: &str) -> Self {
+        match exchange.to_ascii_lowercase().as_str() {
+            "nyse" | "nasdaq" | "amex" | "arca" | "nyse_arca" => MarketCalendar::Nyse,
+            _ => MarketCalendar::Continuous,
+        }
+    }
+
+    /// Regular session of the exchange, `None` when it never closes.
+    pub fn session(&self) -> Option<Session> {
+        match self {
+            MarketCalendar::Continuous => None,
+            MarketCalendar::Nyse => Some(Session::rth()),
+        }
+    }
+
+    /// Holidays falling on weekdays during `year`, sorted.
+    pub fn holidays(&self, year: i32) -> Vec<Date> {
+        match self {
+            MarketCalendar::Continuous => Vec::new(),
+            MarketCalendar::Nyse => nyse_holidays(year),
+        }
+    }
+
+    pub fn is_trading_day(&self, date: Date) -> bool {
+        match self {
+            MarketCalendar::Continuous => true,
+            _ => !date.is_weekend() && !self.holidays(date.year).contains(&date),
+        }
+    }
+
+    /// Whether the exchange is open at `timestamp`.
+    pub fn is_open(&self, timestamp: i64) -> bool {
+        match self.session() {
+            None => true,
+            Some(session) => {
+                session.contains(timestamp)
+                    && self.is_trading_day(Date::from_timestamp(timestamp, session.time_zone))
+            }
+        }
+    }
+
+    /// Start of the first session opening at or after `timestamp`, or `timestamp` itself
+    /// when the exchange is already open.
+    pub fn next_open(&self, timestamp: i64) -> i64 {
+        let Some(session) = self.session() else {
+            return timestamp;
+        };
+        if self.is_open(timestamp) {
+            return timestamp;
+        }
+        let time_zone = session.time_zone;
+        let standard = time_zone.offset_minutes as i64 * 60_000;
+        let mut days = Date::from_timestamp(timestamp, time_zone).days();
+        loop {
+            let date = Date::from_days(days);
+            let local_open = days * DAY_MS + session.start as i64 * 60_000;
+            let open = local_open - time_zone.offset_at(local_open - standard);
+            if open >= timestamp && self.is_trading_day(date) {
+                return open;
+            }
+            days += 1;
+        }
+    }
+}
+
+/// Whether `exchange` has a session on `date`, in its local time.
+pub fn is_trading_day(exchange: &str, date: Date) -> bool {
+    MarketCalendar::for_exchange(exchange).is_trading_day(date)
+}
+
+/// Start of the next session of `exchange` at or after `timestamp`.
+pub fn next_open(exchange: &str, timestamp: i64) -> i64 {
+    MarketCalendar::for_exchange(exchange).next_open(timestamp)
+}
+
+fn nth_weekday(year: i32, month: u8, day_of_week: u8, n: i64) -> i64 {
+    let first = days_from_civil(year, month, 1);
+    first + (day_of_week as i64 - weekday(first) as i64).rem_euclid(7) + 7 * (n - 1)
+}
+
+fn last_weekday(year: i32, month: u8, day_of_week: u8) -> i64 {
+    let last = days_from_civil(year, month + 1, 1) - 1;
+    last - (weekday(last) as i64 - day_of_week as i64).rem_euclid(7)
+}
+
+// Saturday holidays are observed on Friday, Sunday holidays on Monday
+fn observed(days: i64) -> i64 {
+    match weekday(days) {
+        5 => days - 1,
+        6 => days + 1,
+        _ => days,
+    }
+}
+
+// Anonymous Gregorian algorithm
+fn easter(year: i32) -> i64 {
+    let y = year as i64;
+    let a = y % 19;
+    let b = y / 100;
+    let c = y % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    days_from_civil(year, month as u8, day as u8)
+}
+
+fn nyse_holidays(year: i32) -> Vec<Date> {
+    let mut days = Vec::with_capacity(10);
+    // A Saturday New Year's Day is not observed on the last trading day of the year
+    let new_year = days_from_civil(year, 1, 1);
+    if weekday(new_year) != 5 {
+        days.push(observed(new_year));
+    }
+    if year >= 1998 {
+        days.push(nth_weekday(year, 1, 0, 3));
+    }
+    days.push(nth_weekday(year, 2, 0, 3));
+    days.push(easter(year) - 2);
+    days.push(last_weekday(year, 5, 0));
+    if year >= 2022 {
+        days.push(observed(days_from_civil(year, 6, 19)));
+    }
+    days.push(observed(days_from_civil(year, 7, 4)));
+    days.push(nth_weekday(year, 9, 0, 1));
+    days.push(nth_weekday(year, 11, 3, 4));
+    days.push(observed(days_from_civil(year, 12, 25)));
+    days.sort_unstable();
+    days.into_iter().map(Date::from_days).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nyse_holidays() {
+        let holidays: Vec<String> = MarketCalendar::Nyse
+            .holidays(2024)
+            .iter()
+            .map(Date::to_string)
+            .collect();
+        assert_eq!(
+            holidays,
+            vec![
+                "2024-01-01",
+                "2024-01-15",
+                "2024-02-19",
+                "2024-03-29",
+                "2024-05-27",
+                "2024-06-19",
+                "2024-07-04",
+                "2024-09-02",
+                "2024-11-28",
+                "2024-12-25",
+            ]
+        );
+        // 2022-01-01 was a Saturday, 2022-12-25 a Sunday
+        let holidays = MarketCalendar::Nyse.holidays(2022);
+        assert!(!holidays.contains(&Date::new(2021, 12, 31)));
+        assert!(holidays.contains(&Date::new(2022, 12, 26)));
+        assert!(holidays.contains(&Date::new(2022, 6, 20)));
+    }
+
+    #[test]
+    fn test_is_trading_day() {
+        assert!(is_trading_day("NYSE", Date::new(2024, 7, 3)));
+        assert!(!is_trading_day("NYSE", Date::new(2024, 7, 4)));
+        assert!(!is_trading_day("nasdaq", Date::new(2024, 7, 6)));
+        assert!(is_trading_day("binance", Date::new(2024, 12, 25)));
+    }
+
+    #[test]
+    fn test_next_open() {
+        // Wednesday 2024-07-03 21:00 UTC, after the close, Thursday is Independence Day
+        assert_eq!(next_open("NYSE", 1_720_040_400_000), 1_720_186_200_000);
+        // Friday 2024-07-05 15:00 UTC, already open
+        assert_eq!(next_open("NYSE", 1_720_191_600_000), 1_720_191_600_000);
+        // Friday 2024-03-08 22:00 UTC, Monday opens at 13:30 UTC after the DST change
+        assert_eq!(next_open("NYSE", 1_709_935_200_000), 1_710_163_800_000);
+        assert_eq!(next_open("binance", 42), 42);
+    }
+}
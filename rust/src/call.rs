@@ -0,0 +1,128 @@
+//! Runtime composition of published functions: a function can invoke another one and use
+//! its output, in addition to the static pipes configured on the platform.
+use extism_pdk::{Error, Json, WithReturnCode};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::PipeOutput;
+
+#[cfg(any(not(feature = "test-utils"), target_arch = "wasm32"))]
+mod host {
+    use super::FunctionCall;
+    use extism_pdk::{Json, host_fn};
+
+    #[host_fn]
+    extern "ExtismHost" {
+        pub fn call_function(call: Json<FunctionCall>) -> String;
+    }
+}
+
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+mod host {
+    use super::FunctionCall;
+    use crate::testing::host_call;
+    use extism_pdk::{Error, Json};
+
+    pub unsafe fn call_function(Json(call): Json<FunctionCall>) -> Result<String, Error> {
+        host_call("call_function", |host| {
+            let output = host
+                .functions
+                .get(&call.name)
+                .cloned()
+                .ok_or_else(|| Error::msg(format!("no mocked function {}", call.name)))?;
+            host.function_calls.push((call.name, call.call_arguments));
+            Ok(output)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct FunctionCall {
+    name: String,
+    call_arguments: Value,
+}
+
+/// Output of another function, in the same form as the data received from a pipe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipePayload(String);
+
+impl PipePayload {
+    pub fn new(data: impl Into<String>) -> Self {
+        PipePayload(data.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Reads the payload as a [`PipeOutput`] type, checking its content type.
+    ///
+    /// Fails with return code 27 if the payload is not a `T`.
+    pub fn parse_pipe<T: PipeOutput>(&self) -> Result<T, WithReturnCode<Error>> {
+        T::from_pipe(&self.0)
+    }
+
+    /// Deserializes the payload from JSON.
+    ///
+    /// Fails with return code 26 if the payload is not valid JSON for `T`.
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, WithReturnCode<Error>> {
+        serde_json::from_str(&self.0).map_err(|e| {
+            WithReturnCode::new(
+                Error::new(std::io::Error::other(format!(
+                    "Failed to parse function output: {}",
+                    e
+                ))),
+                26,
+            )
+        })
+    }
+}
+
+/// Invokes the published function `name` with `args` as its call arguments and returns
+/// its output. The host provides the market data the called function is configured with.
+///
+/// Fails with return code 25 if the arguments cannot be serialized or the call fails.
+pub fn call_function<A: Serialize>(
+    name: &str,
+    args: &A,
+) -> Result<PipePayload, WithReturnCode<Error>> {
+    let call_error = |e: &dyn std::fmt::Display| {
+        WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "impossible to call function {}: {}",
+                name, e
+            ))),
+            25,
+        )
+    };
+    let call = FunctionCall {
+        name: name.to_string(),
+        call_arguments: serde_json::to_value(args).map_err(|e| call_error(&e))?,
+    };
+    let output = unsafe { host::call_function(Json(call)) }.map_err(|e| call_error(&e))?;
+    Ok(PipePayload(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Signal {
+        value: f64,
+    }
+
+    #[test]
+    fn test_payload_parse() {
+        let payload = PipePayload::new(r#"{"value": 0.5}"#);
+        assert_eq!(payload.parse::<Signal>().unwrap(), Signal { value: 0.5 });
+
+        let err = PipePayload::new("not json").parse::<Signal>().unwrap_err();
+        assert_eq!(err.1, 26);
+    }
+}
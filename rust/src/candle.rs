@@ -1,9 +1,12 @@
+#[cfg(feature = "decimal")]
 use rust_decimal::prelude::*;
 use serde::{Deserialize, Deserializer};
 
 /// Represents a single candlestick in financial data, typically used in trading charts.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Candle<T> {
-    /// The timestamp of the candlestick in milliseconds since the Unix epoch.
+    /// The timestamp of the candlestick in milliseconds since the Unix epoch, whatever the
+    /// unit sent by the exchange, once loaded in [`TickersData`](crate::TickersData).
     pub timestamp: i64,
     /// The opening price of the asset during the candlestick's time period.
     pub open: T,
@@ -16,6 +19,61 @@ pub struct Candle<T> {
     /// The trading volume of the asset during the candlestick's time period.
     pub volume: T,
 }
+/// Unit of a raw timestamp, as sent by exchanges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampUnit {
+    #[serde(alias = "s")]
+    Seconds,
+    #[serde(alias = "ms")]
+    Millis,
+    #[serde(alias = "us")]
+    Micros,
+    #[serde(alias = "ns")]
+    Nanos,
+}
+
+impl TimestampUnit {
+    /// Guesses the unit of a timestamp from its magnitude, assuming it falls between 1973
+    /// and 5138, where the ranges of the units do not overlap.
+    pub fn detect(timestamp: i64) -> Self {
+        match timestamp.unsigned_abs() {
+            0..100_000_000_000 => TimestampUnit::Seconds,
+            100_000_000_000..100_000_000_000_000 => TimestampUnit::Millis,
+            100_000_000_000_000..100_000_000_000_000_000 => TimestampUnit::Micros,
+            _ => TimestampUnit::Nanos,
+        }
+    }
+
+    /// Guesses the unit shared by a series of timestamps from the largest one, so a whole
+    /// series gets the same unit. `None` for an empty series.
+    pub fn detect_series(timestamps: impl IntoIterator<Item = i64>) -> Option<Self> {
+        timestamps
+            .into_iter()
+            .max_by_key(|timestamp| timestamp.unsigned_abs())
+            .map(TimestampUnit::detect)
+    }
+
+    /// Converts `timestamp`, expressed in this unit, to milliseconds, saturating at the
+    /// bounds of `i64`.
+    pub fn to_millis(self, timestamp: i64) -> i64 {
+        match self {
+            TimestampUnit::Seconds => timestamp.saturating_mul(1_000),
+            TimestampUnit::Millis => timestamp,
+            TimestampUnit::Micros => timestamp / 1_000,
+            TimestampUnit::Nanos => timestamp / 1_000_000,
+        }
+    }
+}
+
+/// Converts a timestamp of any [`TimestampUnit`] to milliseconds, detecting its unit.
+///
+/// Millisecond timestamps before 1973 are taken for seconds; when the unit is known, use
+/// [`TimestampUnit::to_millis`] instead.
+pub fn normalize_timestamp(timestamp: i64) -> i64 {
+    TimestampUnit::detect(timestamp).to_millis(timestamp)
+}
+
 // Custom deserialization from an array
 impl<'de, T> Deserialize<'de> for Candle<T>
 where
@@ -37,6 +95,7 @@ where
     }
 }
 
+#[cfg(feature = "decimal")]
 impl Candle<f64> {
     /// Convert candle to a Decimal representation
     pub fn to_decimal(&self, precision: i32) -> Candle<Decimal> {
@@ -60,3 +119,40 @@ impl Candle<f64> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp_normalization() {
+        for raw in [
+            1_700_000_000,
+            1_700_000_000_000,
+            1_700_000_000_000_000,
+            1_700_000_000_000_000_000,
+        ] {
+            assert_eq!(normalize_timestamp(raw), 1_700_000_000_000, "{}", raw);
+        }
+        assert_eq!(TimestampUnit::detect(0), TimestampUnit::Seconds);
+    }
+
+    #[test]
+    fn test_detect_series() {
+        assert_eq!(
+            TimestampUnit::detect_series([0, 60, 1_700_000_000]),
+            Some(TimestampUnit::Seconds)
+        );
+        assert_eq!(
+            TimestampUnit::detect_series([1_000, 1_700_000_000_000]),
+            Some(TimestampUnit::Millis)
+        );
+        assert_eq!(TimestampUnit::detect_series([]), None);
+    }
+
+    #[test]
+    fn test_to_millis_saturates() {
+        assert_eq!(TimestampUnit::Seconds.to_millis(i64::MAX), i64::MAX);
+        assert_eq!(TimestampUnit::Seconds.to_millis(i64::MIN), i64::MIN);
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+use rust_decimal::prelude::*;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A decimal written as a string with a fixed number of decimals, like `"0.30"`, so
+/// prices keep their exact value and precision through `piped_data` and notification
+/// payloads instead of picking up f64 artifacts like `0.30000000000000004`.
+///
+/// It parses back from strings or JSON numbers, keeping the decimals written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecimalString {
+    value: Decimal,
+    precision: u32,
+}
+
+impl DecimalString {
+    /// `value` rounded to `precision` decimals.
+    pub fn new(value: Decimal, precision: u32) -> Self {
+        DecimalString {
+            value: value.round_dp(precision),
+            precision,
+        }
+    }
+
+    /// `value` rounded to `precision` decimals, `None` if it is not finite.
+    pub fn from_f64(value: f64, precision: u32) -> Option<Self> {
+        Decimal::from_f64(value).map(|value| DecimalString::new(value, precision))
+    }
+
+    pub fn value(&self) -> Decimal {
+        self.value
+    }
+
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+}
+
+impl From<DecimalString> for Decimal {
+    fn from(value: DecimalString) -> Self {
+        value.value
+    }
+}
+
+impl fmt::Display for DecimalString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.*}", self.precision as usize, self.value)
+    }
+}
+
+impl FromStr for DecimalString {
+    type Err = rust_decimal::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = Decimal::from_str(s.trim())?;
+        Ok(DecimalString {
+            value,
+            precision: value.scale(),
+        })
+    }
+}
+
+impl Serialize for DecimalString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DecimalString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(DecimalStringVisitor)
+    }
+}
+
+struct DecimalStringVisitor;
+
+impl Visitor<'_> for DecimalStringVisitor {
+    type Value = DecimalString;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a decimal number or string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<DecimalString, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<DecimalString, E> {
+        Ok(DecimalString::new(Decimal::from(value), 0))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<DecimalString, E> {
+        Ok(DecimalString::new(Decimal::from(value), 0))
+    }
+
+    // The shortest representation of the float, so 0.1 parses as 0.1
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<DecimalString, E> {
+        self.visit_str(&value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize_with_precision() {
+        let price = DecimalString::from_f64(0.1 + 0.2, 2).unwrap();
+        assert_eq!(serde_json::to_value(price).unwrap(), json!("0.30"));
+        let price = DecimalString::new(Decimal::from(42), 3);
+        assert_eq!(price.to_string(), "42.000");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let price = DecimalString::from_f64(1234.5678, 2).unwrap();
+        let json = serde_json::to_string(&price).unwrap();
+        let parsed: DecimalString = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, price);
+        assert_eq!(parsed.value(), Decimal::new(123457, 2));
+
+        let number: DecimalString = serde_json::from_str("0.1").unwrap();
+        assert_eq!(number.to_string(), "0.1");
+        assert!(serde_json::from_str::<DecimalString>(r#""abc""#).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt::Display;
+
+use extism_pdk::{Error, WithReturnCode};
+
+/// Error returned by the functions of this crate and by plugin entry points: an error
+/// message with the return code reported to the host.
+pub type AbiError = WithReturnCode<Error>;
+
+/// Adds human-readable context to failures, so an error raised deep in indicator code
+/// reaches the platform logs with the step that failed.
+///
+/// ```ignore
+/// let candles = args
+///     .get_candles(&label)
+///     .context(format!("loading the benchmark {}", label))?;
+/// let period: usize = args.get_call_argument("period").with_context(40, "invalid period")?;
+/// ```
+///
+/// The context is layered on top of the original error, which stays visible in the
+/// message reported to the host.
+pub trait ResultExt<T> {
+    /// Adds context, keeping the return code of the error.
+    fn context(self, message: impl Display) -> Result<T, AbiError>;
+
+    /// Adds context and reports `code` to the host instead of the original return code.
+    fn with_context(self, code: i32, message: impl Display) -> Result<T, AbiError>;
+}
+
+impl<T> ResultExt<T> for Result<T, AbiError> {
+    fn context(self, message: impl Display) -> Result<T, AbiError> {
+        self.map_err(|WithReturnCode(e, code)| WithReturnCode(e.context(message.to_string()), code))
+    }
+
+    fn with_context(self, code: i32, message: impl Display) -> Result<T, AbiError> {
+        self.map_err(|WithReturnCode(e, _)| WithReturnCode(e.context(message.to_string()), code))
+    }
+}
+
+/// Turns a missing value into an error.
+impl<T> ResultExt<T> for Option<T> {
+    /// Fails with return code -1, the code extism gives to errors converted with `?`.
+    fn context(self, message: impl Display) -> Result<T, AbiError> {
+        self.with_context(-1, message)
+    }
+
+    fn with_context(self, code: i32, message: impl Display) -> Result<T, AbiError> {
+        self.ok_or_else(|| WithReturnCode(Error::msg(message.to_string()), code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failing() -> Result<(), AbiError> {
+        Err(WithReturnCode::new(Error::msg("symbol BTC not found"), 1))
+    }
+
+    #[test]
+    fn test_context_keeps_code() {
+        let err = failing().context("loading benchmark").unwrap_err();
+        assert_eq!(err.1, 1);
+        assert_eq!(err.0.to_string(), "loading benchmark");
+        let report = format!("{:?}", err.0);
+        assert!(report.contains("loading benchmark"));
+        assert!(report.contains("symbol BTC not found"));
+    }
+
+    #[test]
+    fn test_with_context_overrides_code() {
+        let err = failing()
+            .context("loading benchmark")
+            .with_context(40, "computing beta")
+            .unwrap_err();
+        assert_eq!(err.1, 40);
+        let chain: Vec<String> = err.0.chain().map(|e| e.to_string()).collect();
+        assert_eq!(
+            chain,
+            vec![
+                "computing beta",
+                "loading benchmark",
+                "symbol BTC not found"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_option_context() {
+        assert_eq!(Some(3).context("missing").unwrap(), 3);
+        assert_eq!(None::<i32>.context("missing").unwrap_err().1, -1);
+        let err = None::<i32>.with_context(41, "no candle").unwrap_err();
+        assert_eq!(err.1, 41);
+        assert_eq!(err.0.to_string(), "no candle");
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Deserializer};
+
+/// Kind of a scheduled event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Earnings,
+    /// Federal Open Market Committee rate decision.
+    Fomc,
+    /// Consumer price index release.
+    Cpi,
+    /// Exchange maintenance window, during which orders may be rejected.
+    Maintenance,
+    #[serde(other)]
+    Other,
+}
+
+/// Expected market impact of an event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Impact {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+/// A scheduled event from the economic calendar, when provided by the host.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Event {
+    pub kind: EventKind,
+    #[serde(default)]
+    pub title: String,
+    /// Start time in milliseconds since the Unix epoch.
+    pub timestamp: i64,
+    /// End time of events lasting a while, like maintenance windows.
+    #[serde(default)]
+    pub end: Option<i64>,
+    #[serde(default)]
+    pub impact: Impact,
+    /// Symbol concerned by the event, `None` for market-wide events.
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// Exchange concerned by the event, `None` for events affecting every exchange.
+    #[serde(default)]
+    pub exchange: Option<String>,
+}
+
+impl Event {
+    /// End time of the event, its start time for instant events.
+    pub fn end_time(&self) -> i64 {
+        self.end.unwrap_or(self.timestamp).max(self.timestamp)
+    }
+
+    /// Whether the event takes place, even partly, in `[start, end)`.
+    pub fn overlaps(&self, start: i64, end: i64) -> bool {
+        self.timestamp < end && self.end_time() >= start
+    }
+
+    /// Whether the event affects `symbol`, market-wide events affecting every symbol.
+    pub fn concerns(&self, symbol: &str) -> bool {
+        self.symbol.as_deref().is_none_or(|s| s == symbol)
+    }
+}
+
+// Events sorted by start time, as expected by the range queries
+pub(crate) fn deserialize_sorted<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Event>, D::Error> {
+    let mut events = Vec::<Event>::deserialize(deserializer)?;
+    events.sort_by_key(|event| event.timestamp);
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_event() {
+        let events: Vec<Event> = serde_json::from_str(
+            r#"[
+                {"kind": "fomc", "title": "Rate decision", "timestamp": 1000, "impact": "high"},
+                {"kind": "earnings", "timestamp": 2000, "symbol": "AAPL"},
+                {"kind": "halving", "timestamp": 3000, "end": 4000}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(events[0].kind, EventKind::Fomc);
+        assert_eq!(events[0].impact, Impact::High);
+        assert_eq!(events[1].impact, Impact::Low);
+        assert_eq!(events[2].kind, EventKind::Other);
+
+        assert!(events[0].concerns("AAPL"));
+        assert!(!events[1].concerns("MSFT"));
+        assert!(events[2].overlaps(3500, 5000));
+        assert!(!events[2].overlaps(4001, 5000));
+        assert!(!events[0].overlaps(0, 1000));
+    }
+}
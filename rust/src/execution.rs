@@ -0,0 +1,90 @@
+use extism_pdk::{Error, Json, WithReturnCode};
+use serde::{Deserialize, Serialize};
+
+#[cfg(any(not(feature = "test-utils"), target_arch = "wasm32"))]
+mod host {
+    use super::ExecutionContext;
+    use extism_pdk::{Json, host_fn};
+
+    #[host_fn]
+    extern "ExtismHost" {
+        pub fn get_current_time() -> Json<i64>;
+        pub fn get_execution_context() -> Json<ExecutionContext>;
+    }
+}
+
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+mod host {
+    use super::ExecutionContext;
+    use crate::testing::host_call;
+    use extism_pdk::{Error, Json};
+
+    pub unsafe fn get_current_time() -> Result<Json<i64>, Error> {
+        host_call("get_current_time", |host| Ok(Json(host.now)))
+    }
+
+    pub unsafe fn get_execution_context() -> Result<Json<ExecutionContext>, Error> {
+        host_call("get_execution_context", |host| {
+            host.execution_context
+                .clone()
+                .map(Json)
+                .ok_or_else(|| Error::msg("no mocked execution context"))
+        })
+    }
+}
+
+/// Whether the function runs against real accounts or in simulation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionMode {
+    /// Orders are simulated by the platform. Used when the host does not specify a mode,
+    /// so a function never trades for real by accident.
+    #[default]
+    Paper,
+    /// Orders are sent to the exchange.
+    Live,
+}
+
+/// Information about the current run of the function.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionContext {
+    /// Unique identifier of this run, useful to tag outputs and notifications.
+    pub run_id: String,
+    /// Time the run was scheduled for, in milliseconds since the Unix epoch. `None` for
+    /// runs triggered manually.
+    #[serde(default)]
+    pub scheduled_at: Option<i64>,
+    /// Time the run actually started, in milliseconds since the Unix epoch.
+    pub started_at: i64,
+    /// Identifier of the user owning the function.
+    pub owner_id: String,
+}
+
+fn context_error(what: &str, e: Error) -> WithReturnCode<Error> {
+    WithReturnCode::new(
+        Error::new(std::io::Error::other(format!(
+            "impossible to get {}: {}",
+            what, e
+        ))),
+        21,
+    )
+}
+
+/// Returns the host's current time in milliseconds since the Unix epoch, the same unit as
+/// candle timestamps.
+///
+/// Fails with return code 21 if the host cannot provide it.
+pub fn now() -> Result<i64, WithReturnCode<Error>> {
+    let Json(now) =
+        unsafe { host::get_current_time() }.map_err(|e| context_error("current time", e))?;
+    Ok(now)
+}
+
+/// Returns information about the current run.
+///
+/// Fails with return code 21 if the host cannot provide it.
+pub fn execution_context() -> Result<ExecutionContext, WithReturnCode<Error>> {
+    let Json(context) = unsafe { host::get_execution_context() }
+        .map_err(|e| context_error("execution context", e))?;
+    Ok(context)
+}
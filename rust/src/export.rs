@@ -0,0 +1,123 @@
+//! Runtime side of the [`exchange_function`](crate::exchange_function) attribute and of
+//! the streaming exports.
+#[cfg(feature = "streaming")]
+use std::any::Any;
+#[cfg(feature = "streaming")]
+use std::cell::RefCell;
+
+use extism_pdk::{Memory, WithReturnCode};
+use serde::Serialize;
+
+#[cfg(feature = "streaming")]
+use crate::stream::{Stream, StreamFunction, Update};
+
+use crate::{AbiError, FunctionArgs, FunctionOutput};
+
+/// Parses the input sent by the host, runs `function` and hands its output or error back
+/// to the host, returning the code the plugin export must return.
+pub fn export<O: Serialize>(function: impl FnOnce(FunctionArgs) -> Result<O, AbiError>) -> i32 {
+    let args = match extism_pdk::input::<FunctionArgs>() {
+        Ok(args) => args,
+        Err(e) => return fail(WithReturnCode::new(e, -1)),
+    };
+    let output = match function(args).and_then(|output| FunctionOutput::new(&output)) {
+        Ok(output) => output,
+        Err(e) => return fail(e),
+    };
+    match extism_pdk::output(output) {
+        Ok(()) => 0,
+        Err(e) => fail(WithReturnCode::new(e, -1)),
+    }
+}
+
+#[cfg(feature = "streaming")]
+thread_local! {
+    // The running stream, a `Stream<S>` of the function given to `export_stream!`
+    static STREAM: RefCell<Option<Box<dyn Any>>> = RefCell::new(None);
+}
+
+/// Starts the stream of `S` from the `FunctionArgs` payload sent by the host, replacing
+/// any running stream. The stream keeps at most
+/// [`S::MAX_CANDLES`](StreamFunction::MAX_CANDLES) candles per ticker.
+#[cfg(feature = "streaming")]
+pub fn stream_init<S: StreamFunction>() -> i32 {
+    let args = match extism_pdk::input::<FunctionArgs>() {
+        Ok(args) => args,
+        Err(e) => return fail(WithReturnCode::new(e, -1)),
+    };
+    match Stream::<S>::start(args) {
+        Ok(stream) => {
+            STREAM.with(|running| *running.borrow_mut() = Some(Box::new(stream)));
+            0
+        }
+        Err(e) => fail(e),
+    }
+}
+
+/// Pushes the update sent by the host into the running stream of `S`, handing the output
+/// of the function back to the host. Fails with code 31 when no stream was started.
+#[cfg(feature = "streaming")]
+pub fn stream_push<S: StreamFunction>() -> i32 {
+    let update = match extism_pdk::input::<extism_pdk::Json<Update>>() {
+        Ok(extism_pdk::Json(update)) => update,
+        Err(e) => return fail(WithReturnCode::new(e, -1)),
+    };
+    let result = STREAM.with(|running| {
+        let mut running = running.borrow_mut();
+        let stream = running
+            .as_mut()
+            .and_then(|stream| stream.downcast_mut::<Stream<S>>())
+            .ok_or(WithReturnCode::new(
+                extism_pdk::Error::new(std::io::Error::other("Stream not started")),
+                31,
+            ))?;
+        stream.push(update)
+    });
+    let output = match result {
+        Ok(Some(output)) => FunctionOutput::new(&output),
+        Ok(None) => return 0,
+        Err(e) => return fail(e),
+    };
+    match output.map(extism_pdk::output) {
+        Ok(Ok(())) => 0,
+        Ok(Err(e)) => fail(WithReturnCode::new(e, -1)),
+        Err(e) => fail(e),
+    }
+}
+
+// Reports the error message to the host the same way extism's plugin_fn does
+fn fail(WithReturnCode(e, code): AbiError) -> i32 {
+    if let Ok(memory) = Memory::from_bytes(format!("{:?}", e)) {
+        unsafe { extism_pdk::extism::error_set(memory.offset()) };
+    }
+    code
+}
+
+#[cfg(all(test, feature = "macros"))]
+mod tests {
+    use serde::Serialize;
+
+    use crate::{AbiError, FunctionArgs, exchange_function};
+
+    #[derive(Debug, PartialEq, Serialize)]
+    pub struct Signal {
+        buy: bool,
+    }
+
+    #[exchange_function]
+    pub fn run(args: FunctionArgs) -> Result<Signal, AbiError> {
+        let threshold: f64 = args.get_call_argument("threshold")?;
+        Ok(Signal {
+            buy: threshold > 1.0,
+        })
+    }
+
+    #[test]
+    fn test_entry_point_stays_callable() {
+        let args = FunctionArgs::from_json_str(
+            r#"{"tickers_data": {}, "piped_data": {}, "call_arguments": {"threshold": 2}}"#,
+        )
+        .unwrap();
+        assert_eq!(run(args).unwrap(), Signal { buy: true });
+    }
+}
@@ -1,43 +1,251 @@
 use extism_pdk::FromBytesOwned;
 use extism_pdk::*;
+#[cfg(feature = "decimal")]
 use rust_decimal::prelude::*;
 use serde::{Deserialize, de::DeserializeOwned};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
-use crate::Candle;
+#[cfg(feature = "calendar")]
+use crate::events::{Event, Impact};
+#[cfg(feature = "news")]
+use crate::news::{self, NewsItem};
+#[cfg(feature = "options")]
+use crate::options::OptionsChain;
+#[cfg(feature = "analytics")]
+use crate::stats;
+use crate::{Candle, ExecutionMode, PipeOutput, Session, Symbol, Timeframe, TimestampUnit};
+#[cfg(feature = "decimal")]
+use crate::{Currency, DecimalString, FxRates, MarketInfo, Portfolio};
 
-#[derive(Deserialize)]
+#[derive(Debug, Deserialize)]
+#[serde(from = "RawTickersData")]
 pub struct TickersData {
-    pub symbol: String,
-    pub exchange: String,
+    pub symbol: Symbol,
+    pub exchange: Symbol,
+    /// Candles, sent by the host as a JSON array or, with the `binary` feature, in the
+    /// compact `binary` encoding.
     pub candles: Vec<Candle<f64>>,
+    /// Unit of the timestamps sent by the host, detected from the candles when not
+    /// provided. The candle timestamps are converted to milliseconds on deserialization
+    /// whatever it is.
+    pub timestamp_unit: Option<TimestampUnit>,
     pub precision: i32,
+    /// Trading rules of the market, when provided by the host.
+    #[cfg(feature = "decimal")]
+    pub market_info: Option<MarketInfo>,
+    /// Interval of the candles, when provided by the host.
+    pub timeframe: Option<Timeframe>,
+    /// Options listed on the ticker, when provided by the host.
+    #[cfg(feature = "options")]
+    pub options_chain: Option<OptionsChain>,
+    /// News about the ticker sorted by timestamp, when provided by the host.
+    #[cfg(feature = "news")]
+    pub news: Vec<NewsItem>,
+}
+
+// TickersData as sent by the host, before the candle timestamps are normalized
+#[derive(Deserialize)]
+struct RawTickersData {
+    symbol: Symbol,
+    exchange: Symbol,
+    #[cfg_attr(
+        feature = "binary",
+        serde(deserialize_with = "crate::binary::deserialize_candles")
+    )]
+    candles: Vec<Candle<f64>>,
+    #[serde(default)]
+    timestamp_unit: Option<TimestampUnit>,
+    precision: i32,
+    #[cfg(feature = "decimal")]
+    #[serde(default)]
+    market_info: Option<MarketInfo>,
+    #[serde(default)]
+    timeframe: Option<Timeframe>,
+    #[cfg(feature = "options")]
+    #[serde(default)]
+    options_chain: Option<OptionsChain>,
+    #[cfg(feature = "news")]
+    #[serde(default, deserialize_with = "crate::news::deserialize_sorted")]
+    news: Vec<NewsItem>,
+}
+
+impl From<RawTickersData> for TickersData {
+    fn from(raw: RawTickersData) -> Self {
+        let mut data = TickersData {
+            symbol: raw.symbol,
+            exchange: raw.exchange,
+            candles: raw.candles,
+            timestamp_unit: raw.timestamp_unit,
+            precision: raw.precision,
+            #[cfg(feature = "decimal")]
+            market_info: raw.market_info,
+            timeframe: raw.timeframe,
+            #[cfg(feature = "options")]
+            options_chain: raw.options_chain,
+            #[cfg(feature = "news")]
+            news: raw.news,
+        };
+        data.normalize_timestamps();
+        data
+    }
 }
 
 impl TickersData {
+    // Converts the candle timestamps to milliseconds, once on deserialization
+    fn normalize_timestamps(&mut self) {
+        let unit = self
+            .timestamp_unit
+            .or_else(|| TimestampUnit::detect_series(self.candles.iter().map(|c| c.timestamp)));
+        if let Some(unit) = unit {
+            for candle in &mut self.candles {
+                candle.timestamp = unit.to_millis(candle.timestamp);
+            }
+        }
+        self.timestamp_unit = unit;
+    }
     pub fn get_candles_iter(&self) -> impl Iterator<Item = &Candle<f64>> {
         self.candles.iter()
     }
     pub fn get_candles(&self) -> &Vec<Candle<f64>> {
         &self.candles
     }
+    #[cfg(feature = "decimal")]
     pub fn get_candles_decimal_iter(&self) -> impl Iterator<Item = Candle<Decimal>> {
         let precision = self.precision;
         self.candles
             .iter()
             .map(move |candle| candle.to_decimal(precision))
     }
+    #[cfg(feature = "decimal")]
     pub fn get_candles_decimal(&self) -> Vec<Candle<Decimal>> {
         self.get_candles_decimal_iter().collect()
     }
+    /// `value` rounded to the ticker precision, serialized as a string, to report prices
+    /// without f64 artifacts.
+    #[cfg(feature = "decimal")]
+    pub fn to_decimal_string(&self, value: f64) -> Option<DecimalString> {
+        DecimalString::from_f64(value, self.precision.max(0) as u32)
+    }
+    /// Like [`get_candles_decimal`](Self::get_candles_decimal), writing into `out`
+    /// (cleared first) to reuse its allocation across calls.
+    #[cfg(feature = "decimal")]
+    pub fn get_candles_decimal_into(&self, out: &mut Vec<Candle<Decimal>>) {
+        out.clear();
+        out.extend(self.get_candles_decimal_iter());
+    }
+    /// Appends a new candle, or replaces the last one when it has the same timestamp, e.g.
+    /// a bar still forming. Candles older than the last one are ignored.
+    pub fn push_candle(&mut self, candle: Candle<f64>) {
+        match self.candles.last_mut() {
+            Some(last) if last.timestamp == candle.timestamp => *last = candle,
+            Some(last) if last.timestamp > candle.timestamp => {}
+            _ => self.candles.push(candle),
+        }
+    }
+    /// Candles in consecutive chunks of `len` candles, the last one possibly shorter, to
+    /// process long histories piece by piece.
+    ///
+    /// Panics if `len` is zero.
+    pub fn chunks(&self, len: usize) -> std::slice::Chunks<'_, Candle<f64>> {
+        self.candles.chunks(len)
+    }
+    /// Folds the candles chunk by chunk, carrying `state` from one chunk to the next.
+    ///
+    /// Panics if `len` is zero.
+    pub fn fold_chunks<S>(&self, len: usize, init: S, f: impl FnMut(S, &[Candle<f64>]) -> S) -> S {
+        self.chunks(len).fold(init, f)
+    }
+    /// Candles opening while `session` is open, e.g. regular trading hours only.
+    pub fn filter_session(&self, session: Session) -> impl Iterator<Item = &Candle<f64>> {
+        self.candles
+            .iter()
+            .filter(move |candle| session.contains(candle.timestamp))
+    }
+    /// Interval of the candles, inferred from the smallest gap between consecutive
+    /// timestamps when the host did not provide it.
+    pub fn get_timeframe(&self) -> Option<Timeframe> {
+        self.timeframe.or_else(|| {
+            self.candles
+                .windows(2)
+                .map(|pair| pair[1].timestamp - pair[0].timestamp)
+                .filter(|gap| *gap > 0)
+                .min()
+                .and_then(Timeframe::from_millis)
+        })
+    }
+    /// Average sentiment of the news published in `[start, end)`.
+    #[cfg(feature = "news")]
+    pub fn average_sentiment(&self, start: i64, end: i64) -> Option<f64> {
+        news::average_sentiment(&self.news, start, end)
+    }
+    /// Average sentiment of the news published during the `window` milliseconds up to
+    /// each candle, one value per candle.
+    #[cfg(feature = "news")]
+    pub fn rolling_sentiment(&self, window: i64) -> Vec<Option<f64>> {
+        let timestamps: Vec<i64> = self.candles.iter().map(|c| c.timestamp).collect();
+        news::rolling_sentiment(&self.news, &timestamps, window)
+    }
+    #[cfg(feature = "options")]
+    pub fn get_options_chain(&self) -> Result<&OptionsChain, WithReturnCode<Error>> {
+        self.options_chain.as_ref().ok_or(WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "Options chain for {} not available",
+                self.symbol
+            ))),
+            28,
+        ))
+    }
+    #[cfg(feature = "decimal")]
+    pub fn get_market_info(&self) -> Result<&MarketInfo, WithReturnCode<Error>> {
+        self.market_info.as_ref().ok_or(WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "Market info for {} not available",
+                self.symbol
+            ))),
+            10,
+        ))
+    }
 }
 
-#[derive(Deserialize)]
+/// Close series of several tickers sharing the same timestamps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignedCloses {
+    pub timestamps: Vec<i64>,
+    /// One close series per ticker, each with one value per timestamp.
+    pub closes: Vec<Vec<f64>>,
+}
+
+#[cfg(feature = "analytics")]
+impl AlignedCloses {
+    /// Simple close-to-close returns of every series.
+    pub fn returns(&self) -> Vec<Vec<f64>> {
+        self.closes
+            .iter()
+            .map(|closes| stats::returns_from_equity(closes))
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
 pub struct FunctionArgs {
-    tickers_data: HashMap<String, TickersData>,
+    tickers_data: HashMap<Symbol, TickersData>,
     piped_data: HashMap<String, String>,
     call_arguments: HashMap<String, Value>,
+    #[serde(default)]
+    execution_mode: ExecutionMode,
+    #[cfg(feature = "decimal")]
+    #[serde(default)]
+    portfolio: Option<Portfolio>,
+    #[cfg(feature = "decimal")]
+    #[serde(default)]
+    fx_rates: Option<FxRates>,
+    #[cfg(feature = "calendar")]
+    #[serde(default, deserialize_with = "crate::events::deserialize_sorted")]
+    events: Vec<Event>,
+    #[serde(skip)]
+    decoded_arguments: Mutex<HashMap<String, Option<Arc<Value>>>>,
 }
 
 impl FromBytesOwned for FunctionArgs {
@@ -47,10 +255,113 @@ impl FromBytesOwned for FunctionArgs {
 }
 
 impl FunctionArgs {
-    pub fn get_labels(&self) -> Vec<&String> {
+    /// Parses the arguments from the JSON payload sent by the host, e.g. a payload captured
+    /// in production and kept as a test fixture.
+    pub fn from_json_str(json: &str) -> Result<Self, extism_pdk::Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Parses the arguments from a reader over the JSON payload sent by the host, e.g. a
+    /// fixture file.
+    ///
+    /// ```ignore
+    /// let args = FunctionArgs::from_reader(File::open("tests/fixtures/btc_1h.json")?)?;
+    /// ```
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self, extism_pdk::Error> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Whether the function runs in live or paper trading mode, to pass to the
+    /// [`trading`](crate::trading) calls.
+    pub fn get_execution_mode(&self) -> ExecutionMode {
+        self.execution_mode
+    }
+
+    /// Whether orders placed by this invocation are simulated.
+    pub fn is_paper(&self) -> bool {
+        self.execution_mode == ExecutionMode::Paper
+    }
+
+    /// The user's holdings and cash, when provided by the host.
+    #[cfg(feature = "decimal")]
+    pub fn get_portfolio(&self) -> Result<&Portfolio, WithReturnCode<Error>> {
+        self.portfolio.as_ref().ok_or(WithReturnCode::new(
+            Error::new(std::io::Error::other("Portfolio not available")),
+            29,
+        ))
+    }
+
+    /// Scheduled events sent by the host, sorted by start time.
+    #[cfg(feature = "calendar")]
+    pub fn get_events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Events taking place, even partly, in `[start, end)`.
+    #[cfg(feature = "calendar")]
+    pub fn events_between(&self, start: i64, end: i64) -> impl Iterator<Item = &Event> {
+        self.events
+            .iter()
+            .filter(move |event| event.overlaps(start, end))
+    }
+
+    /// Whether an event of at least `min_impact` is under way at `timestamp` or starts
+    /// within `window` milliseconds after it, to hold off opening positions.
+    #[cfg(feature = "calendar")]
+    pub fn is_blackout(&self, timestamp: i64, window: i64, min_impact: Impact) -> bool {
+        self.events_between(timestamp, timestamp.saturating_add(window).saturating_add(1))
+            .any(|event| event.impact >= min_impact)
+    }
+
+    /// Exchange rates, when provided by the host.
+    ///
+    /// Fails with return code 30 if the host did not provide them.
+    #[cfg(feature = "decimal")]
+    pub fn get_fx_rates(&self) -> Result<&FxRates, WithReturnCode<Error>> {
+        self.fx_rates.as_ref().ok_or(WithReturnCode::new(
+            Error::new(std::io::Error::other("Exchange rates not available")),
+            30,
+        ))
+    }
+
+    /// Converts `amount` between two currencies with the exchange rates of the host, e.g.
+    /// to report balances held on several exchanges in a single currency.
+    ///
+    /// Fails with return code 30 if the host did not provide exchange rates and 32 if
+    /// either currency has no rate.
+    #[cfg(feature = "decimal")]
+    pub fn convert(
+        &self,
+        amount: Decimal,
+        from: &str,
+        to: &str,
+    ) -> Result<Decimal, WithReturnCode<Error>> {
+        self.get_fx_rates()?
+            .convert(amount, &Currency::new(from), &Currency::new(to))
+    }
+
+    /// Last close of the most recent candle among the tickers of `symbol`, rounded to the
+    /// ticker precision.
+    #[cfg(feature = "decimal")]
+    pub fn latest_close(&self, symbol: &str) -> Option<Decimal> {
+        self.tickers_data
+            .values()
+            .filter(|data| data.symbol == symbol)
+            .filter_map(|data| data.candles.last().map(|c| (c, data.precision)))
+            .max_by_key(|(candle, _)| candle.timestamp)
+            .map(|(candle, precision)| candle.to_decimal(precision).close)
+    }
+
+    pub fn get_labels(&self) -> Vec<&str> {
+        self.tickers_data.keys().map(Symbol::as_str).collect()
+    }
+
+    /// The ticker labels in their interned form, cheap to clone and compare.
+    pub fn get_symbols(&self) -> Vec<&Symbol> {
         self.tickers_data.keys().collect()
     }
 
+    #[allow(clippy::bind_instead_of_map, clippy::io_other_error)]
     pub fn get_candles(&self, label: &str) -> Result<&Vec<Candle<f64>>, WithReturnCode<Error>> {
         self.tickers_data
             .get(label)
@@ -76,6 +387,7 @@ impl FunctionArgs {
         self.piped_data.keys().collect()
     }
 
+    #[allow(clippy::io_other_error)]
     pub fn get_data_from_pipe(&self, source: &str) -> Result<&String, WithReturnCode<Error>> {
         self.piped_data.get(source).ok_or(WithReturnCode::new(
             Error::new(std::io::Error::new(
@@ -86,6 +398,14 @@ impl FunctionArgs {
         ))
     }
 
+    /// Reads the data piped from `source` as a [`PipeOutput`] type.
+    ///
+    /// Fails with return code 2 if the source is missing and 27 if its data is not a `T`.
+    pub fn get_pipe<T: PipeOutput>(&self, source: &str) -> Result<T, WithReturnCode<Error>> {
+        T::from_pipe(self.get_data_from_pipe(source)?)
+    }
+
+    #[allow(clippy::io_other_error)]
     pub fn get_ticker(&self, label: &str) -> Result<&TickersData, WithReturnCode<Error>> {
         self.tickers_data.get(label).ok_or(WithReturnCode::new(
             Error::new(std::io::Error::new(
@@ -95,7 +415,17 @@ impl FunctionArgs {
             3,
         ))
     }
+    pub fn get_ticker_mut(
+        &mut self,
+        label: &str,
+    ) -> Result<&mut TickersData, WithReturnCode<Error>> {
+        self.tickers_data.get_mut(label).ok_or(WithReturnCode::new(
+            Error::new(std::io::Error::other(format!("Ticker {} not found", label))),
+            3,
+        ))
+    }
     /// Returns the candles as Decimal, precision is taken from the ticker
+    #[cfg(feature = "decimal")]
     pub fn get_candles_decimal_iter(
         &self,
         label: &str,
@@ -104,6 +434,7 @@ impl FunctionArgs {
         Ok(ticker.get_candles_decimal_iter())
     }
     /// Returns the candles as Decimal, precision is taken from the ticker
+    #[cfg(feature = "decimal")]
     pub fn get_candles_decimal(
         &self,
         label: &str,
@@ -111,54 +442,183 @@ impl FunctionArgs {
         Ok(self.get_candles_decimal_iter(label)?.collect())
     }
 
+    /// Like [`get_candles_decimal`](Self::get_candles_decimal), writing into `out`
+    /// (cleared first) to reuse its allocation across calls.
+    #[cfg(feature = "decimal")]
+    pub fn get_candles_decimal_into(
+        &self,
+        label: &str,
+        out: &mut Vec<Candle<Decimal>>,
+    ) -> Result<(), WithReturnCode<Error>> {
+        self.get_ticker(label)?.get_candles_decimal_into(out);
+        Ok(())
+    }
+
+    /// Aligns the close series of several tickers on the timestamps they all share.
+    ///
+    /// Candles whose timestamp is missing from any of the tickers are dropped, so every
+    /// series in the result has the same length as `timestamps`, in the order of `labels`.
+    pub fn get_aligned_closes(
+        &self,
+        labels: &[&str],
+    ) -> Result<AlignedCloses, WithReturnCode<Error>> {
+        let tickers = labels
+            .iter()
+            .map(|label| self.get_ticker(label))
+            .collect::<Result<Vec<_>, _>>()?;
+        let lookups: Vec<HashMap<i64, f64>> = tickers
+            .iter()
+            .map(|t| t.candles.iter().map(|c| (c.timestamp, c.close)).collect())
+            .collect();
+        let mut aligned = AlignedCloses {
+            timestamps: Vec::new(),
+            closes: vec![Vec::new(); labels.len()],
+        };
+        let Some(first) = tickers.first() else {
+            return Ok(aligned);
+        };
+        for candle in &first.candles {
+            let row: Option<Vec<f64>> = lookups
+                .iter()
+                .map(|lookup| lookup.get(&candle.timestamp).copied())
+                .collect();
+            if let Some(row) = row {
+                aligned.timestamps.push(candle.timestamp);
+                for (series, close) in aligned.closes.iter_mut().zip(row) {
+                    series.push(close);
+                }
+            }
+        }
+        Ok(aligned)
+    }
+
+    /// Correlation matrix of the close-to-close returns of several tickers, computed on
+    /// their shared timestamps. Rows and columns follow the order of `labels`.
+    #[cfg(feature = "analytics")]
+    pub fn get_correlation_matrix(
+        &self,
+        labels: &[&str],
+    ) -> Result<Vec<Vec<f64>>, WithReturnCode<Error>> {
+        Ok(stats::correlation_matrix(
+            &self.get_aligned_closes(labels)?.returns(),
+        ))
+    }
+
+    /// Covariance matrix of the close-to-close returns of several tickers, computed on
+    /// their shared timestamps. Rows and columns follow the order of `labels`.
+    #[cfg(feature = "analytics")]
+    pub fn get_covariance_matrix(
+        &self,
+        labels: &[&str],
+    ) -> Result<Vec<Vec<f64>>, WithReturnCode<Error>> {
+        Ok(stats::covariance_matrix(
+            &self.get_aligned_closes(labels)?.returns(),
+        ))
+    }
+
+    /// Rolling beta of a ticker against a benchmark ticker, over `window` close-to-close
+    /// returns computed on their shared timestamps.
+    ///
+    /// Each point carries the timestamp of the last candle of its window. Windows where
+    /// the benchmark does not move are skipped.
+    #[cfg(feature = "analytics")]
+    pub fn get_rolling_beta(
+        &self,
+        label: &str,
+        benchmark: &str,
+        window: usize,
+    ) -> Result<Vec<(i64, f64)>, WithReturnCode<Error>> {
+        let aligned = self.get_aligned_closes(&[label, benchmark])?;
+        let returns = aligned.returns();
+        if window == 0 || returns[0].len() < window {
+            return Ok(Vec::new());
+        }
+        Ok((window..=returns[0].len())
+            .filter_map(|end| {
+                let beta = stats::beta(
+                    &returns[0][end - window..end],
+                    &returns[1][end - window..end],
+                )?;
+                Some((aligned.timestamps[end], beta))
+            })
+            .collect())
+    }
+
+    /// Relative strength of a ticker against a benchmark ticker: the ratio of their
+    /// closes on shared timestamps, normalized to 1 on the first shared candle.
+    pub fn get_relative_strength(
+        &self,
+        label: &str,
+        benchmark: &str,
+    ) -> Result<Vec<(i64, f64)>, WithReturnCode<Error>> {
+        let aligned = self.get_aligned_closes(&[label, benchmark])?;
+        let ratios: Vec<f64> = aligned.closes[0]
+            .iter()
+            .zip(&aligned.closes[1])
+            .map(|(close, bench)| close / bench)
+            .collect();
+        let Some(&base) = ratios.first() else {
+            return Ok(Vec::new());
+        };
+        Ok(aligned
+            .timestamps
+            .into_iter()
+            .zip(ratios)
+            .map(|(timestamp, ratio)| (timestamp, ratio / base))
+            .collect())
+    }
+
     // Returns the call arguments as a HashMap
     pub fn get_call_arguments(&self) -> &HashMap<String, Value> {
         &self.call_arguments
     }
-    pub fn get_call_argument<T: DeserializeOwned>(&self, key: &str) -> Result<T, WithReturnCode<Error>> {
-        let arg = self.call_arguments.get(key).ok_or(WithReturnCode::new(
-            Error::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Call argument {} not found", key),
-            )),
+    /// The raw value of a call argument, to inspect it without deserializing it.
+    pub fn get_call_argument_ref(&self, key: &str) -> Result<&Value, WithReturnCode<Error>> {
+        self.call_arguments.get(key).ok_or(WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "Call argument {} not found",
+                key
+            ))),
             4,
-        ))?;
-        let res = serde_json::from_value::<T>(arg.clone()).map_err(|e| {
-            WithReturnCode::new(
-                Error::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("Failed to parse call argument {}: {}", key, e),
-                )),
+        ))
+    }
+    pub fn get_call_argument<T: DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Result<T, WithReturnCode<Error>> {
+        let arg = self.get_call_argument_ref(key)?;
+        let e = match T::deserialize(arg) {
+            Ok(value) => return Ok(value),
+            Err(e) => WithReturnCode::new(
+                Error::new(std::io::Error::other(format!(
+                    "Failed to parse call argument {}: {}",
+                    key, e
+                ))),
                 5,
-            )
-        });
-        match res {
-            Ok(value) => Ok(value),
-            Err(e) => {
-                // Try to parse as string and then convert to the desired type
-                if let Some(arg_str) = arg.as_str() {
-                    let res_str = serde_json::from_str::<T>(arg_str).map_err(|e| {
-                        WithReturnCode::new(
-                            Error::new(std::io::Error::new(
-                                std::io::ErrorKind::Other,
-                                format!("Failed to parse call argument {} as string: {}", key, e),
-                            )),
-                            6,
-                        )
-                    });
-                    match res_str {
-                        Ok(value) => Ok(value),
-                        Err(_) => Err(e),
-                    }
-                } else {
-                    Err(e)
-                }
-            }
+            ),
+        };
+        // Try to parse as string and then convert to the desired type
+        match arg.as_str().and_then(|s| self.decoded_argument(key, s)) {
+            Some(decoded) => T::deserialize(decoded.as_ref()).map_err(|_| e),
+            None => Err(e),
         }
     }
+
+    // JSON held in a string argument, parsed once per invocation
+    fn decoded_argument(&self, key: &str, arg: &str) -> Option<Arc<Value>> {
+        let mut decoded = self
+            .decoded_arguments
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        decoded
+            .entry(key.to_string())
+            .or_insert_with(|| serde_json::from_str(arg).ok().map(Arc::new))
+            .clone()
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::approx_constant, clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     use serde::{Deserialize, Serialize};
@@ -213,9 +673,209 @@ mod tests {
             tickers_data: HashMap::new(),
             piped_data: HashMap::new(),
             call_arguments,
+            execution_mode: ExecutionMode::default(),
+            #[cfg(feature = "decimal")]
+            portfolio: None,
+            #[cfg(feature = "decimal")]
+            fx_rates: None,
+            #[cfg(feature = "calendar")]
+            events: Vec::new(),
+            decoded_arguments: Mutex::default(),
+        }
+    }
+
+    fn ticker(symbol: &str, closes: &[(i64, f64)]) -> TickersData {
+        TickersData {
+            symbol: Symbol::new(symbol),
+            exchange: Symbol::new("test"),
+            candles: closes
+                .iter()
+                .map(|&(timestamp, close)| Candle {
+                    timestamp,
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 1.0,
+                })
+                .collect(),
+            timestamp_unit: None,
+            precision: 2,
+            #[cfg(feature = "decimal")]
+            market_info: None,
+            timeframe: None,
+            #[cfg(feature = "options")]
+            options_chain: None,
+            #[cfg(feature = "news")]
+            news: Vec::new(),
         }
     }
 
+    fn create_test_tickers_args() -> FunctionArgs {
+        let mut tickers_data = HashMap::new();
+        tickers_data.insert(
+            Symbol::new("a"),
+            ticker("A", &[(1, 10.0), (2, 11.0), (3, 12.1), (4, 11.0)]),
+        );
+        tickers_data.insert(
+            Symbol::new("b"),
+            ticker("B", &[(1, 20.0), (3, 24.2), (4, 22.0), (5, 30.0)]),
+        );
+        FunctionArgs {
+            tickers_data,
+            piped_data: HashMap::new(),
+            call_arguments: HashMap::new(),
+            execution_mode: ExecutionMode::default(),
+            #[cfg(feature = "decimal")]
+            portfolio: None,
+            #[cfg(feature = "decimal")]
+            fx_rates: None,
+            #[cfg(feature = "calendar")]
+            events: Vec::new(),
+            decoded_arguments: Mutex::default(),
+        }
+    }
+
+    #[test]
+    fn test_get_aligned_closes() {
+        let args = create_test_tickers_args();
+        let aligned = args.get_aligned_closes(&["a", "b"]).unwrap();
+        assert_eq!(aligned.timestamps, vec![1, 3, 4]);
+        assert_eq!(
+            aligned.closes,
+            vec![vec![10.0, 12.1, 11.0], vec![20.0, 24.2, 22.0]]
+        );
+        assert!(args.get_aligned_closes(&["a", "missing"]).is_err());
+    }
+
+    #[cfg(feature = "analytics")]
+    #[test]
+    fn test_get_correlation_matrix() {
+        let args = create_test_tickers_args();
+        let matrix = args.get_correlation_matrix(&["a", "b"]).unwrap();
+        assert!((matrix[0][1] - 1.0).abs() < 1e-9);
+        assert!((matrix[1][0] - 1.0).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "analytics")]
+    #[test]
+    fn test_get_rolling_beta() {
+        let args = create_test_tickers_args();
+        // "b" moves exactly like "a" on shared timestamps
+        let betas = args.get_rolling_beta("a", "b", 2).unwrap();
+        assert_eq!(betas.len(), 1);
+        assert_eq!(betas[0].0, 4);
+        assert!((betas[0].1 - 1.0).abs() < 1e-9);
+        assert!(args.get_rolling_beta("a", "b", 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_relative_strength() {
+        let args = create_test_tickers_args();
+        let strength = args.get_relative_strength("a", "b").unwrap();
+        assert_eq!(strength.len(), 3);
+        assert_eq!(strength[0], (1, 1.0));
+        assert!(strength.iter().all(|(_, rs)| (rs - 1.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_execution_mode() {
+        let args = FunctionArgs::from_bytes_owned(
+            br#"{"tickers_data": {}, "piped_data": {}, "call_arguments": {}}"#,
+        )
+        .unwrap();
+        assert!(args.is_paper());
+        let args = FunctionArgs::from_bytes_owned(
+            br#"{"tickers_data": {}, "piped_data": {}, "call_arguments": {}, "execution_mode": "live"}"#,
+        )
+        .unwrap();
+        assert_eq!(args.get_execution_mode(), ExecutionMode::Live);
+    }
+
+    #[test]
+    fn test_from_json_fixture() {
+        let fixture = r#"{
+            "tickers_data": {
+                "btc": {
+                    "symbol": "BTCUSDT",
+                    "exchange": "binance",
+                    "candles": [[1000, 1.0, 2.0, 0.5, 1.5, 10.0]],
+                    "precision": 2
+                }
+            },
+            "piped_data": {},
+            "call_arguments": {"window": 14}
+        }"#;
+        let args = FunctionArgs::from_json_str(fixture).unwrap();
+        assert_eq!(args.get_candles("btc").unwrap()[0].close, 1.5);
+        assert_eq!(args.get_call_argument::<i32>("window").unwrap(), 14);
+
+        let args = FunctionArgs::from_reader(fixture.as_bytes()).unwrap();
+        assert_eq!(args.get_ticker("btc").unwrap().symbol, "BTCUSDT");
+        assert!(FunctionArgs::from_json_str("{}").is_err());
+    }
+
+    #[test]
+    fn test_labels_and_symbols() {
+        let args = FunctionArgs::from_json_str(
+            r#"{"tickers_data": {"btc": {"symbol": "BTCUSDT", "exchange": "binance",
+                "precision": 2, "candles": []}}, "piped_data": {}, "call_arguments": {}}"#,
+        )
+        .unwrap();
+        assert_eq!(args.get_labels(), vec!["btc"]);
+        assert_eq!(args.get_symbols(), vec![&Symbol::new("btc")]);
+    }
+
+    #[test]
+    fn test_timestamp_unit() {
+        let args = FunctionArgs::from_json_str(
+            r#"{
+            "tickers_data": {
+                "seconds": {"symbol": "AAPL", "exchange": "nasdaq", "precision": 2,
+                    "candles": [[1699999940, 1, 1, 1, 1, 1], [1700000000, 1, 1, 1, 1, 1]]},
+                "millis": {"symbol": "BTCUSDT", "exchange": "binance", "precision": 2,
+                    "timestamp_unit": "ms", "candles": [[1000, 1, 1, 1, 1, 1]]},
+                "micros": {"symbol": "ETHUSDT", "exchange": "binance", "precision": 2,
+                    "timestamp_unit": "micros", "candles": [[60000000, 1, 1, 1, 1, 1]]}
+            },
+            "piped_data": {},
+            "call_arguments": {}
+        }"#,
+        )
+        .unwrap();
+        let seconds = args.get_ticker("seconds").unwrap();
+        assert_eq!(seconds.timestamp_unit, Some(TimestampUnit::Seconds));
+        assert_eq!(seconds.candles[1].timestamp, 1_700_000_000_000);
+        assert_eq!(args.get_candles("millis").unwrap()[0].timestamp, 1000);
+        assert_eq!(args.get_candles("micros").unwrap()[0].timestamp, 60_000);
+    }
+
+    #[test]
+    fn test_timestamp_unit_plain_deserialize() {
+        let data: TickersData = serde_json::from_str(
+            r#"{"symbol": "AAPL", "exchange": "nasdaq", "precision": 2,
+                "candles": [[1700000000, 1, 1, 1, 1, 1]]}"#,
+        )
+        .unwrap();
+        assert_eq!(data.candles[0].timestamp, 1_700_000_000_000);
+
+        let args: FunctionArgs = serde_json::from_str(
+            r#"{
+            "tickers_data": {
+                "nanos": {"symbol": "ETHUSDT", "exchange": "binance", "precision": 2,
+                    "candles": [[1700000000000000000, 1, 1, 1, 1, 1]]}
+            },
+            "piped_data": {},
+            "call_arguments": {}
+        }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            args.get_candles("nanos").unwrap()[0].timestamp,
+            1_700_000_000_000
+        );
+    }
+
     #[test]
     fn test_get_call_argument_string() {
         let args = create_test_function_args();
@@ -331,4 +991,132 @@ mod tests {
         let result: Result<String, WithReturnCode<Error>> = args.get_call_argument("non_existent_arg");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_get_timeframe() {
+        let mut data = ticker("A", &[(0, 1.0), (7_200_000, 1.0), (10_800_000, 1.0)]);
+        assert_eq!(data.get_timeframe(), Some(Timeframe::hours(1)));
+        data.timeframe = Some(Timeframe::hours(4));
+        assert_eq!(data.get_timeframe(), Some(Timeframe::hours(4)));
+        assert_eq!(ticker("B", &[(0, 1.0)]).get_timeframe(), None);
+    }
+
+    #[test]
+    fn test_filter_session() {
+        // 2024-01-02 14:00, 14:30 and 21:00 UTC
+        let data = ticker(
+            "A",
+            &[
+                (1_704_204_000_000, 1.0),
+                (1_704_205_800_000, 2.0),
+                (1_704_229_200_000, 3.0),
+            ],
+        );
+        let closes: Vec<f64> = data
+            .filter_session(Session::rth())
+            .map(|candle| candle.close)
+            .collect();
+        assert_eq!(closes, vec![2.0]);
+    }
+
+    #[test]
+    fn test_get_call_argument_ref() {
+        let args = create_test_function_args();
+        assert_eq!(args.get_call_argument_ref("int_arg").unwrap(), &json!(42));
+        assert_eq!(args.get_call_argument_ref("missing").unwrap_err().1, 4);
+    }
+
+    #[test]
+    fn test_string_argument_decoded_once() {
+        let args = create_test_function_args();
+        let first: Vec<i32> = args.get_call_argument("array_str_arg").unwrap();
+        let second: Vec<i64> = args.get_call_argument("array_str_arg").unwrap();
+        assert_eq!(first, vec![1, 2, 3]);
+        assert_eq!(second, vec![1, 2, 3]);
+        assert_eq!(args.decoded_arguments.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_fold_chunks() {
+        let data = ticker("A", &[(1, 1.0), (2, 2.0), (3, 3.0), (4, 4.0), (5, 5.0)]);
+        let lens: Vec<usize> = data.chunks(2).map(<[_]>::len).collect();
+        assert_eq!(lens, vec![2, 2, 1]);
+        let (count, sum) = data.fold_chunks(2, (0, 0.0), |(count, sum), chunk| {
+            (count + 1, sum + chunk.iter().map(|c| c.close).sum::<f64>())
+        });
+        assert_eq!((count, sum), (3, 15.0));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_portfolio() {
+        let args = FunctionArgs::from_json_str(
+            r#"{
+                "tickers_data": {
+                    "btc_1h": {"symbol": "BTCUSDT", "exchange": "binance", "precision": 2,
+                               "candles": [[1700000000000, 1, 1, 1, 100.004, 1]]},
+                    "btc_1d": {"symbol": "BTCUSDT", "exchange": "binance", "precision": 2,
+                               "candles": [[1699920000000, 1, 1, 1, 90, 1]]}
+                },
+                "piped_data": {},
+                "call_arguments": {},
+                "portfolio": {"holdings": [{"symbol": "BTCUSDT", "quantity": 2, "cost_basis": 80}], "cash": 10},
+                "fx_rates": {"base": "USDT", "rates": {"EUR": "0.5"}}
+            }"#,
+        )
+        .unwrap();
+        let portfolio = args.get_portfolio().unwrap();
+        let price = |h: &crate::Holding| args.latest_close(&h.symbol);
+        assert_eq!(portfolio.equity(price), Some(Decimal::from(210)));
+        assert_eq!(args.latest_close("ETHUSDT"), None);
+        assert_eq!(
+            create_test_function_args().get_portfolio().unwrap_err().1,
+            29
+        );
+    }
+
+    #[cfg(feature = "calendar")]
+    #[test]
+    fn test_events() {
+        let args = FunctionArgs::from_json_str(
+            r#"{
+                "tickers_data": {},
+                "piped_data": {},
+                "call_arguments": {},
+                "events": [
+                    {"kind": "cpi", "timestamp": 5000, "impact": "high"},
+                    {"kind": "maintenance", "timestamp": 1000, "end": 2000, "exchange": "binance"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(args.get_events()[0].timestamp, 1000);
+        assert_eq!(args.events_between(1500, 5000).count(), 1);
+        assert_eq!(args.events_between(0, 10_000).count(), 2);
+        assert!(args.is_blackout(4000, 1000, Impact::High));
+        assert!(!args.is_blackout(3000, 1000, Impact::High));
+        assert!(args.is_blackout(1500, 0, Impact::Low));
+        assert!(!args.is_blackout(1500, 0, Impact::Medium));
+        assert!(args.is_blackout(4000, i64::MAX, Impact::High));
+    }
+
+    #[cfg(feature = "news")]
+    #[test]
+    fn test_ticker_news() {
+        let data: TickersData = serde_json::from_str(
+            r#"{"symbol": "AAPL", "exchange": "nasdaq", "precision": 2,
+                "candles": [[1700000000000, 1, 1, 1, 1, 1], [1700000060000, 1, 1, 1, 1, 1]],
+                "news": [
+                    {"timestamp": 1700000030000, "headline": "Beat", "score": 0.5},
+                    {"timestamp": 1699999990000, "headline": "Rumor", "score": -0.5}
+                ]}"#,
+        )
+        .unwrap();
+        assert_eq!(data.news[0].headline, "Rumor");
+        assert_eq!(data.rolling_sentiment(60_000), vec![Some(-0.5), Some(0.5)]);
+        assert_eq!(
+            data.average_sentiment(1699999990000, 1700000060000),
+            Some(0.0)
+        );
+    }
 }
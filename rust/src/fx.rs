@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use extism_pdk::{Error, WithReturnCode};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer};
+
+use crate::Symbol;
+
+/// Currency code, like `USD` or `USDT`, compared case-insensitively.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Currency(Symbol);
+
+impl Currency {
+    pub fn new(code: &str) -> Self {
+        Currency(Symbol::new(&code.to_ascii_uppercase()))
+    }
+
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Currency {
+    fn from(code: &str) -> Self {
+        Currency::new(code)
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = Symbol::deserialize(deserializer)?;
+        Ok(Currency::new(&code))
+    }
+}
+
+/// An amount in a given currency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Money {
+    pub amount: Decimal,
+    pub currency: Currency,
+}
+
+impl Money {
+    pub fn new(amount: Decimal, currency: impl Into<Currency>) -> Self {
+        Money {
+            amount,
+            currency: currency.into(),
+        }
+    }
+
+    /// Sum of two amounts, `None` if they are in different currencies.
+    pub fn checked_add(&self, other: &Money) -> Option<Money> {
+        (self.currency == other.currency)
+            .then(|| Money::new(self.amount + other.amount, self.currency.clone()))
+    }
+
+    /// The same value in `to`.
+    pub fn convert(
+        &self,
+        to: impl Into<Currency>,
+        rates: &FxRates,
+    ) -> Result<Money, WithReturnCode<Error>> {
+        let to = to.into();
+        let amount = rates.convert(self.amount, &self.currency, &to)?;
+        Ok(Money::new(amount, to))
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+/// Exchange rates provided by the host, as the price of one unit of `base` in every
+/// other currency.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FxRates {
+    pub base: Currency,
+    pub rates: HashMap<Currency, Decimal>,
+}
+
+impl FxRates {
+    /// Units of `currency` for one unit of the base currency.
+    pub fn rate(&self, currency: &Currency) -> Option<Decimal> {
+        if *currency == self.base {
+            return Some(Decimal::ONE);
+        }
+        self.rates
+            .get(currency)
+            .copied()
+            .filter(|rate| !rate.is_zero())
+    }
+
+    /// Converts `amount` from one currency to another, going through the base currency.
+    ///
+    /// Fails with return code 32 if either currency has no rate.
+    pub fn convert(
+        &self,
+        amount: Decimal,
+        from: &Currency,
+        to: &Currency,
+    ) -> Result<Decimal, WithReturnCode<Error>> {
+        if from == to {
+            return Ok(amount);
+        }
+        match (self.rate(from), self.rate(to)) {
+            (Some(from_rate), Some(to_rate)) => Ok(amount / from_rate * to_rate),
+            _ => Err(WithReturnCode::new(
+                Error::new(std::io::Error::other(format!(
+                    "No exchange rate from {} to {}",
+                    from, to
+                ))),
+                32,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rates() -> FxRates {
+        serde_json::from_str(r#"{"base": "usd", "rates": {"EUR": "0.8", "JPY": 150}}"#).unwrap()
+    }
+
+    #[test]
+    fn test_convert() {
+        let rates = rates();
+        let usd = Currency::new("USD");
+        let eur = Currency::new("eur");
+        let jpy = Currency::new("JPY");
+        assert_eq!(
+            rates.convert(Decimal::from(10), &usd, &eur).unwrap(),
+            Decimal::from(8)
+        );
+        assert_eq!(
+            rates.convert(Decimal::from(8), &eur, &jpy).unwrap(),
+            Decimal::from(1500)
+        );
+        let error = rates
+            .convert(Decimal::ONE, &usd, &Currency::new("GBP"))
+            .unwrap_err();
+        assert_eq!(error.1, 32);
+    }
+
+    #[test]
+    fn test_money() {
+        let rates = rates();
+        let a = Money::new(Decimal::from(10), "EUR");
+        let b = Money::new(Decimal::from(6), "eur");
+        assert_eq!(
+            a.checked_add(&b),
+            Some(Money::new(Decimal::from(16), "EUR"))
+        );
+        assert_eq!(a.checked_add(&Money::new(Decimal::ONE, "USD")), None);
+        let converted = a.convert("USD", &rates).unwrap();
+        assert_eq!(converted.to_string(), "12.5 USD");
+    }
+}
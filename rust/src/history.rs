@@ -0,0 +1,88 @@
+//! On-demand candle history, for when a function needs a longer lookback or another
+//! timeframe than the host pre-loaded into the function arguments.
+use std::ops::Range;
+
+use extism_pdk::{Error, Json, WithReturnCode};
+use serde::Serialize;
+
+use crate::{TickersData, Timeframe};
+
+#[cfg(any(not(feature = "test-utils"), target_arch = "wasm32"))]
+mod host {
+    use super::CandleQuery;
+    use crate::TickersData;
+    use extism_pdk::{Json, host_fn};
+
+    #[host_fn]
+    extern "ExtismHost" {
+        pub fn fetch_candles(query: Json<CandleQuery>) -> Json<TickersData>;
+    }
+}
+
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+mod host {
+    use super::CandleQuery;
+    use crate::TickersData;
+    use crate::testing::host_call;
+    use extism_pdk::{Error, Json};
+
+    pub unsafe fn fetch_candles(
+        Json(query): Json<CandleQuery>,
+    ) -> Result<Json<TickersData>, Error> {
+        host_call("fetch_candles", |host| {
+            let key = (query.symbol, query.exchange, query.timeframe);
+            let data = host
+                .candles
+                .get(&key)
+                .ok_or_else(|| Error::msg(format!("no mocked candles for {:?}", key)))?;
+            let mut data: TickersData = serde_json::from_value(data.clone())?;
+            data.candles
+                .retain(|c| (query.from..query.to).contains(&c.timestamp));
+            Ok(Json(data))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct CandleQuery {
+    symbol: String,
+    exchange: String,
+    timeframe: Timeframe,
+    /// Inclusive start, in milliseconds since the Unix epoch.
+    from: i64,
+    /// Exclusive end, in milliseconds since the Unix epoch.
+    to: i64,
+}
+
+/// Fetches the candles of `symbol` on `exchange` at `timeframe` whose timestamps fall in
+/// `range`, in milliseconds since the Unix epoch.
+///
+/// Taking a [`Timeframe`] rather than a string catches an invalid interval before the
+/// host call: parse user input with `"1h".parse::<Timeframe>()`.
+///
+/// The host may return fewer candles than requested if the history is not available.
+/// Fails with return code 24 if the host cannot serve the request.
+pub fn fetch_candles(
+    symbol: &str,
+    exchange: &str,
+    timeframe: Timeframe,
+    range: Range<i64>,
+) -> Result<TickersData, WithReturnCode<Error>> {
+    let query = CandleQuery {
+        symbol: symbol.to_string(),
+        exchange: exchange.to_string(),
+        timeframe,
+        from: range.start,
+        to: range.end,
+    };
+    let Json(data) = unsafe { host::fetch_candles(Json(query)) }.map_err(|e| {
+        WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "impossible to fetch {} candles for {} on {}: {}",
+                timeframe, symbol, exchange, e
+            ))),
+            24,
+        )
+    })?;
+    Ok(data)
+}
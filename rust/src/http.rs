@@ -0,0 +1,182 @@
+//! JSON convenience wrappers over the Extism HTTP host call.
+//!
+//! The host only allows requests to the hosts listed in the plugin manifest. Extism does
+//! not expose per-request timeouts: a slow request counts against the execution time
+//! budget of the function, which the host enforces.
+use std::collections::BTreeMap;
+
+use extism_pdk::{Error, HttpRequest, WithReturnCode};
+use serde::{Serialize, de::DeserializeOwned};
+
+#[cfg(any(not(feature = "test-utils"), target_arch = "wasm32"))]
+mod host {
+    use extism_pdk::{Error, HttpRequest};
+
+    pub fn request(request: &HttpRequest, body: Option<Vec<u8>>) -> Result<(u16, Vec<u8>), Error> {
+        let response = extism_pdk::http::request(request, body)?;
+        Ok((response.status_code(), response.body()))
+    }
+}
+
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+mod host {
+    use crate::testing::{RecordedRequest, host_call};
+    use extism_pdk::{Error, HttpRequest};
+
+    pub fn request(request: &HttpRequest, body: Option<Vec<u8>>) -> Result<(u16, Vec<u8>), Error> {
+        host_call("http_request", |host| {
+            let method = request.method.clone().unwrap_or_else(|| "GET".to_string());
+            host.http_requests.push(RecordedRequest {
+                method: method.clone(),
+                url: request.url.clone(),
+                headers: request.headers.clone(),
+                body,
+            });
+            host.http_responses
+                .get(&(method.to_uppercase(), request.url.clone()))
+                .cloned()
+                .ok_or_else(|| {
+                    Error::msg(format!("no mocked response for {} {}", method, request.url))
+                })
+        })
+    }
+}
+
+/// Sends requests with a common set of headers, e.g. an API key.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HttpClient {
+    headers: BTreeMap<String, String>,
+}
+
+impl HttpClient {
+    pub fn new() -> Self {
+        HttpClient::default()
+    }
+
+    pub fn with_header(mut self, key: &str, value: &str) -> Self {
+        self.headers.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    fn request(&self, url: &str, method: &str) -> HttpRequest {
+        let mut request = HttpRequest::new(url)
+            .with_method(method)
+            .with_header("Accept", "application/json");
+        for (key, value) in &self.headers {
+            request = request.with_header(key.as_str(), value.as_str());
+        }
+        request
+    }
+
+    /// Sends a GET request and parses the JSON response.
+    ///
+    /// Fails with return code 18 if the request cannot be sent, 19 if the server answers
+    /// with a non-success status and 20 if the response is not the expected JSON.
+    pub fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, WithReturnCode<Error>> {
+        self.send(url, self.request(url, "GET"), None)
+    }
+
+    /// Sends a POST request with a JSON body and parses the JSON response.
+    ///
+    /// Fails with the same return codes as [`get_json`](Self::get_json).
+    pub fn post_json<B: Serialize, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: &B,
+    ) -> Result<T, WithReturnCode<Error>> {
+        let body = serde_json::to_vec(body).map_err(|e| request_error(url, e))?;
+        let request = self
+            .request(url, "POST")
+            .with_header("Content-Type", "application/json");
+        self.send(url, request, Some(body))
+    }
+
+    fn send<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        request: HttpRequest,
+        body: Option<Vec<u8>>,
+    ) -> Result<T, WithReturnCode<Error>> {
+        let (status, body) = host::request(&request, body).map_err(|e| request_error(url, e))?;
+        parse_response(url, status, &body)
+    }
+}
+
+/// Sends a GET request without extra headers and parses the JSON response.
+pub fn get_json<T: DeserializeOwned>(url: &str) -> Result<T, WithReturnCode<Error>> {
+    HttpClient::new().get_json(url)
+}
+
+/// Sends a POST request with a JSON body and parses the JSON response.
+pub fn post_json<B: Serialize, T: DeserializeOwned>(
+    url: &str,
+    body: &B,
+) -> Result<T, WithReturnCode<Error>> {
+    HttpClient::new().post_json(url, body)
+}
+
+fn request_error(url: &str, e: impl std::fmt::Display) -> WithReturnCode<Error> {
+    WithReturnCode::new(
+        Error::new(std::io::Error::other(format!(
+            "impossible to send request to {}: {}",
+            url, e
+        ))),
+        18,
+    )
+}
+
+fn parse_response<T: DeserializeOwned>(
+    url: &str,
+    status: u16,
+    body: &[u8],
+) -> Result<T, WithReturnCode<Error>> {
+    if !(200..300).contains(&status) {
+        return Err(WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "Request to {} failed with status {}: {}",
+                url,
+                status,
+                String::from_utf8_lossy(body)
+            ))),
+            19,
+        ));
+    }
+    serde_json::from_slice(body).map_err(|e| {
+        WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "Failed to parse response from {}: {}",
+                url, e
+            ))),
+            20,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn test_parse_response() {
+        let value: Value = parse_response("u", 200, br#"{"price": 1}"#).unwrap();
+        assert_eq!(value["price"], 1);
+        assert_eq!(
+            parse_response::<Value>("u", 404, b"missing").unwrap_err().1,
+            19
+        );
+        assert_eq!(
+            parse_response::<Value>("u", 200, b"<html>").unwrap_err().1,
+            20
+        );
+    }
+
+    #[test]
+    fn test_request_headers() {
+        let client = HttpClient::new().with_header("X-Api-Key", "secret");
+        let request = client.request("https://example.com", "GET");
+        assert_eq!(request.method.as_deref(), Some("GET"));
+        assert_eq!(request.headers["X-Api-Key"], "secret");
+        assert_eq!(request.headers["Accept"], "application/json");
+    }
+}
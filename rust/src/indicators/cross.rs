@@ -0,0 +1,142 @@
+use crate::Candle;
+
+/// A value of a series that may be missing, such as an indicator still warming up.
+///
+/// Implemented for `f64` and `Option<f64>`, so crossings can be detected both on raw
+/// series and on the output of [`Indicator::compute`](super::Indicator::compute).
+pub trait SeriesValue {
+    fn value(&self) -> Option<f64>;
+}
+
+impl SeriesValue for f64 {
+    fn value(&self) -> Option<f64> {
+        Some(*self)
+    }
+}
+
+impl SeriesValue for Option<f64> {
+    fn value(&self) -> Option<f64> {
+        *self
+    }
+}
+
+/// Direction of a crossing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossDirection {
+    Above,
+    Below,
+}
+
+/// Indexes in `a` where `a` moves from below or equal to `b` to strictly above it.
+///
+/// Series of different lengths are aligned on their last element, as happens with
+/// rolling outputs that are shorter at the start. Positions where either side is
+/// missing never count as a crossing.
+pub fn crossed_above<A: SeriesValue, B: SeriesValue>(a: &[A], b: &[B]) -> Vec<usize> {
+    crossings(a, b, CrossDirection::Above)
+}
+
+/// Indexes in `a` where `a` moves from above or equal to `b` to strictly below it.
+///
+/// Alignment follows [`crossed_above`].
+pub fn crossed_below<A: SeriesValue, B: SeriesValue>(a: &[A], b: &[B]) -> Vec<usize> {
+    crossings(a, b, CrossDirection::Below)
+}
+
+/// Indexes where `series` crosses a constant `level`, with the direction of each crossing.
+pub fn crossed_level<A: SeriesValue>(series: &[A], level: f64) -> Vec<(usize, CrossDirection)> {
+    let mut out = Vec::new();
+    for i in 1..series.len() {
+        if let (Some(prev), Some(curr)) = (series[i - 1].value(), series[i].value()) {
+            if prev <= level && curr > level {
+                out.push((i, CrossDirection::Above));
+            } else if prev >= level && curr < level {
+                out.push((i, CrossDirection::Below));
+            }
+        }
+    }
+    out
+}
+
+/// Maps crossing indexes of a series of `series_len` values to candle timestamps,
+/// aligning the series on the last candle.
+pub fn crossing_timestamps(
+    candles: &[Candle<f64>],
+    series_len: usize,
+    indexes: &[usize],
+) -> Vec<i64> {
+    let offset = candles.len().saturating_sub(series_len);
+    indexes
+        .iter()
+        .filter_map(|&i| candles.get(offset + i).map(|c| c.timestamp))
+        .collect()
+}
+
+fn crossings<A: SeriesValue, B: SeriesValue>(
+    a: &[A],
+    b: &[B],
+    direction: CrossDirection,
+) -> Vec<usize> {
+    let len = a.len().min(b.len());
+    let (a_offset, b_offset) = (a.len() - len, b.len() - len);
+    let diff = |i: usize| Some(a[a_offset + i].value()? - b[b_offset + i].value()?);
+    let mut out = Vec::new();
+    for i in 1..len {
+        if let (Some(prev), Some(curr)) = (diff(i - 1), diff(i)) {
+            let crossed = match direction {
+                CrossDirection::Above => prev <= 0.0 && curr > 0.0,
+                CrossDirection::Below => prev >= 0.0 && curr < 0.0,
+            };
+            if crossed {
+                out.push(a_offset + i);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossed_above_below() {
+        let a = [1.0, 2.0, 3.0, 2.0, 1.0];
+        let b = [2.0, 2.0, 2.0, 2.0, 2.0];
+        assert_eq!(crossed_above(&a, &b), vec![2]);
+        assert_eq!(crossed_below(&a, &b), vec![4]);
+    }
+
+    #[test]
+    fn test_crossed_with_missing_and_shorter_series() {
+        let a = [Some(5.0), None, Some(1.0), Some(3.0)];
+        let b = [2.0, 2.0];
+        assert_eq!(crossed_above(&a, &b), vec![3]);
+        let a = [Some(1.0), None, Some(3.0)];
+        assert!(crossed_above(&a, &[2.0, 2.0, 2.0]).is_empty());
+    }
+
+    #[test]
+    fn test_crossed_level() {
+        let series = [10.0, 30.0, 30.0, 25.0, 35.0];
+        assert_eq!(
+            crossed_level(&series, 30.0),
+            vec![(3, CrossDirection::Below), (4, CrossDirection::Above)]
+        );
+    }
+
+    #[test]
+    fn test_crossing_timestamps() {
+        let candles: Vec<_> = (0..5)
+            .map(|i| Candle {
+                timestamp: i * 10,
+                open: 0.0,
+                high: 0.0,
+                low: 0.0,
+                close: 0.0,
+                volume: 0.0,
+            })
+            .collect();
+        assert_eq!(crossing_timestamps(&candles, 3, &[1, 2]), vec![30, 40]);
+    }
+}
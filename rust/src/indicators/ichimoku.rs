@@ -0,0 +1,260 @@
+use std::collections::VecDeque;
+
+use crate::Candle;
+
+use super::{Indicator, donchian_mid, is_stale};
+
+/// Periods used to compute the Ichimoku lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IchimokuParams {
+    /// Lookback of the conversion line (tenkan-sen).
+    pub tenkan: usize,
+    /// Lookback of the base line (kijun-sen).
+    pub kijun: usize,
+    /// Lookback of the leading span B (senkou span B).
+    pub senkou_b: usize,
+    /// Number of candles the leading spans are shifted forward and the lagging span backward.
+    pub displacement: usize,
+}
+
+impl Default for IchimokuParams {
+    fn default() -> Self {
+        IchimokuParams {
+            tenkan: 9,
+            kijun: 26,
+            senkou_b: 52,
+            displacement: 26,
+        }
+    }
+}
+
+/// The full Ichimoku Kinko Hyo set.
+///
+/// Each line is a series of `(timestamp, value)` points. The leading spans are plotted
+/// `displacement` candles ahead of the candle they are computed on, so their last points
+/// have timestamps past the last candle, extrapolated from the last candle interval.
+/// The lagging span is the close plotted `displacement` candles in the past.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ichimoku {
+    /// Conversion line, midpoint of the last `tenkan` candles.
+    pub tenkan: Vec<(i64, f64)>,
+    /// Base line, midpoint of the last `kijun` candles.
+    pub kijun: Vec<(i64, f64)>,
+    /// Leading span A, average of tenkan and kijun shifted forward.
+    pub senkou_a: Vec<(i64, f64)>,
+    /// Leading span B, midpoint of the last `senkou_b` candles shifted forward.
+    pub senkou_b: Vec<(i64, f64)>,
+    /// Lagging span, the close shifted backward.
+    pub chikou: Vec<(i64, f64)>,
+}
+
+impl Ichimoku {
+    /// Computes the Ichimoku lines over the given candles, ordered by timestamp.
+    pub fn compute(candles: &[Candle<f64>], params: IchimokuParams) -> Self {
+        let interval = match candles {
+            [.., prev, last] => last.timestamp - prev.timestamp,
+            _ => 0,
+        };
+        // Timestamp of the candle at `index`, extrapolated past the end of the series
+        let timestamp_at = |index: usize| match candles.get(index) {
+            Some(candle) => candle.timestamp,
+            None => {
+                let last = candles.len() - 1;
+                candles[last].timestamp + (index - last) as i64 * interval
+            }
+        };
+        let mid = |period: usize, index: usize| {
+            (period > 0 && index + 1 >= period)
+                .then(|| donchian_mid(&candles[index + 1 - period..=index]))
+        };
+
+        let mut ichimoku = Ichimoku {
+            tenkan: Vec::new(),
+            kijun: Vec::new(),
+            senkou_a: Vec::new(),
+            senkou_b: Vec::new(),
+            chikou: Vec::new(),
+        };
+        for (i, candle) in candles.iter().enumerate() {
+            let tenkan = mid(params.tenkan, i);
+            let kijun = mid(params.kijun, i);
+            let shifted = timestamp_at(i + params.displacement);
+            if let Some(tenkan) = tenkan {
+                ichimoku.tenkan.push((candle.timestamp, tenkan));
+            }
+            if let Some(kijun) = kijun {
+                ichimoku.kijun.push((candle.timestamp, kijun));
+            }
+            if let (Some(tenkan), Some(kijun)) = (tenkan, kijun) {
+                ichimoku.senkou_a.push((shifted, (tenkan + kijun) / 2.0));
+            }
+            if let Some(senkou_b) = mid(params.senkou_b, i) {
+                ichimoku.senkou_b.push((shifted, senkou_b));
+            }
+            if i >= params.displacement {
+                ichimoku
+                    .chikou
+                    .push((candles[i - params.displacement].timestamp, candle.close));
+            }
+        }
+        ichimoku
+    }
+}
+
+/// Ichimoku values computed on the latest candle.
+///
+/// The leading spans are not shifted: they belong `displacement` candles ahead of the
+/// candle that produced them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IchimokuValue {
+    pub tenkan: f64,
+    pub kijun: f64,
+    pub senkou_a: f64,
+    pub senkou_b: f64,
+}
+
+/// Streaming form of [`Ichimoku`], keeping only the candles needed by the longest lookback.
+#[derive(Debug, Clone)]
+pub struct IchimokuIndicator {
+    params: IchimokuParams,
+    // (high, low) of the most recent candles
+    window: VecDeque<(f64, f64)>,
+    last_timestamp: Option<i64>,
+}
+
+impl IchimokuIndicator {
+    pub fn new(params: IchimokuParams) -> Self {
+        IchimokuIndicator {
+            params,
+            window: VecDeque::new(),
+            last_timestamp: None,
+        }
+    }
+
+    fn mid(&self, period: usize) -> f64 {
+        let (high, low) = self.window.iter().rev().take(period).fold(
+            (f64::NEG_INFINITY, f64::INFINITY),
+            |(high, low), &(h, l)| (high.max(h), low.min(l)),
+        );
+        (high + low) / 2.0
+    }
+
+    fn value(&self) -> Option<IchimokuValue> {
+        if self.window.len() < self.warmup_len() {
+            return None;
+        }
+        let tenkan = self.mid(self.params.tenkan);
+        let kijun = self.mid(self.params.kijun);
+        Some(IchimokuValue {
+            tenkan,
+            kijun,
+            senkou_a: (tenkan + kijun) / 2.0,
+            senkou_b: self.mid(self.params.senkou_b),
+        })
+    }
+}
+
+impl Indicator for IchimokuIndicator {
+    type Output = IchimokuValue;
+
+    fn update(&mut self, candle: &Candle<f64>) -> Option<IchimokuValue> {
+        if is_stale(&mut self.last_timestamp, candle) {
+            return self.value();
+        }
+        if self.window.len() == self.warmup_len() {
+            self.window.pop_front();
+        }
+        self.window.push_back((candle.high, candle.low));
+        self.value()
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.last_timestamp = None;
+    }
+
+    fn warmup_len(&self) -> usize {
+        self.params
+            .tenkan
+            .max(self.params.kijun)
+            .max(self.params.senkou_b)
+            .max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candles(n: usize) -> Vec<Candle<f64>> {
+        (0..n)
+            .map(|i| {
+                let base = i as f64;
+                Candle {
+                    timestamp: 1_000 + i as i64 * 60_000,
+                    open: base,
+                    high: base + 1.0,
+                    low: base - 1.0,
+                    close: base + 0.5,
+                    volume: 10.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ichimoku_lengths() {
+        let candles = candles(60);
+        let ichimoku = Ichimoku::compute(&candles, IchimokuParams::default());
+        assert_eq!(ichimoku.tenkan.len(), 60 - 8);
+        assert_eq!(ichimoku.kijun.len(), 60 - 25);
+        assert_eq!(ichimoku.senkou_a.len(), 60 - 25);
+        assert_eq!(ichimoku.senkou_b.len(), 60 - 51);
+        assert_eq!(ichimoku.chikou.len(), 60 - 26);
+    }
+
+    #[test]
+    fn test_ichimoku_values() {
+        let candles = candles(60);
+        let ichimoku = Ichimoku::compute(&candles, IchimokuParams::default());
+        // Tenkan of candles 0..=8: high 9, low -1
+        assert_eq!(ichimoku.tenkan[0], (candles[8].timestamp, 4.0));
+        // Kijun of candles 0..=25: high 26, low -1
+        assert_eq!(ichimoku.kijun[0], (candles[25].timestamp, 12.5));
+        // Chikou is the close of candle 26 plotted at candle 0
+        assert_eq!(ichimoku.chikou[0], (candles[0].timestamp, 26.5));
+    }
+
+    #[test]
+    fn test_ichimoku_forward_shift() {
+        let candles = candles(60);
+        let ichimoku = Ichimoku::compute(&candles, IchimokuParams::default());
+        // Span A computed on candle 25 is plotted on candle 51
+        assert_eq!(ichimoku.senkou_a[0].0, candles[51].timestamp);
+        // Span computed on the last candle is plotted 26 intervals after it
+        let last = candles.last().unwrap().timestamp;
+        assert_eq!(ichimoku.senkou_a.last().unwrap().0, last + 26 * 60_000);
+        assert_eq!(ichimoku.senkou_b.last().unwrap().0, last + 26 * 60_000);
+    }
+
+    #[test]
+    fn test_ichimoku_indicator_matches_batch() {
+        let candles = candles(60);
+        let ichimoku = Ichimoku::compute(&candles, IchimokuParams::default());
+        let mut indicator = IchimokuIndicator::new(IchimokuParams::default());
+        let values = indicator.compute(&candles);
+        assert!(values[50].is_none());
+        let last = values[59].unwrap();
+        assert_eq!(last.tenkan, ichimoku.tenkan.last().unwrap().1);
+        assert_eq!(last.kijun, ichimoku.kijun.last().unwrap().1);
+        assert_eq!(last.senkou_a, ichimoku.senkou_a.last().unwrap().1);
+        assert_eq!(last.senkou_b, ichimoku.senkou_b.last().unwrap().1);
+    }
+
+    #[test]
+    fn test_ichimoku_empty() {
+        let ichimoku = Ichimoku::compute(&[], IchimokuParams::default());
+        assert!(ichimoku.tenkan.is_empty());
+        assert!(ichimoku.chikou.is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+//! Technical indicators computed over candle series.
+//!
+//! Indicators that displace values in time (like the Ichimoku spans) produce series of
+//! `(timestamp, value)` points so they keep a correct position on the chart.
+mod cross;
+mod ichimoku;
+mod pivots;
+mod rolling;
+mod streaming;
+mod volatility;
+mod volume;
+
+pub use cross::{
+    CrossDirection, SeriesValue, crossed_above, crossed_below, crossed_level, crossing_timestamps,
+};
+pub use ichimoku::{Ichimoku, IchimokuIndicator, IchimokuParams, IchimokuValue};
+pub use pivots::{
+    Level, LevelKind, PivotKind, PivotLevels, SwingKind, SwingPoint, pivot_points,
+    support_resistance, swing_points,
+};
+pub use rolling::{Rolling, RollingExt};
+pub use streaming::{Atr, Ema, FormingBar, Rsi, load_state, save_state};
+pub use volatility::{annualize, garman_klass, parkinson, yang_zhang};
+pub use volume::{Mfi, Obv, VolumeBucket, VolumeProfile};
+
+use crate::Candle;
+
+/// A streaming indicator fed one candle at a time.
+///
+/// All built-in indicators implement this trait so they can be composed generically,
+/// e.g. as `Vec<Box<dyn Indicator<Output = f64>>>`, and custom indicators can be
+/// plugged into the same pipelines.
+pub trait Indicator {
+    /// The value produced for each candle.
+    type Output;
+
+    /// Feeds the next candle and returns the current value, or `None` while warming up.
+    fn update(&mut self, candle: &Candle<f64>) -> Option<Self::Output>;
+
+    /// Clears the internal state, as if no candle had been fed.
+    fn reset(&mut self);
+
+    /// Number of candles needed before the indicator produces a value.
+    fn warmup_len(&self) -> usize;
+
+    /// Feeds every candle in order and returns the value produced for each of them.
+    fn compute(&mut self, candles: &[Candle<f64>]) -> Vec<Option<Self::Output>>
+    where
+        Self: Sized,
+    {
+        candles.iter().map(|candle| self.update(candle)).collect()
+    }
+
+    /// Like [`compute`](Self::compute), writing into `out` (cleared first) to reuse its
+    /// allocation across calls.
+    fn compute_into(&mut self, candles: &[Candle<f64>], out: &mut Vec<Option<Self::Output>>)
+    where
+        Self: Sized,
+    {
+        out.clear();
+        out.extend(candles.iter().map(|candle| self.update(candle)));
+    }
+
+    /// Feeds the candles in chunks of `len`, calling `f` with each chunk and its values,
+    /// so memory stays bounded by the chunk size however long the history is.
+    ///
+    /// The state carries over between chunks, giving the same values as
+    /// [`compute`](Self::compute). Panics if `len` is zero.
+    fn compute_chunks<F>(&mut self, candles: &[Candle<f64>], len: usize, mut f: F)
+    where
+        Self: Sized,
+        F: FnMut(&[Candle<f64>], &[Option<Self::Output>]),
+    {
+        let mut out = Vec::with_capacity(len.min(candles.len()));
+        for chunk in candles.chunks(len) {
+            self.compute_into(chunk, &mut out);
+            f(chunk, &out);
+        }
+    }
+}
+
+impl<I: Indicator + ?Sized> Indicator for Box<I> {
+    type Output = I::Output;
+
+    fn update(&mut self, candle: &Candle<f64>) -> Option<Self::Output> {
+        (**self).update(candle)
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+
+    fn warmup_len(&self) -> usize {
+        (**self).warmup_len()
+    }
+}
+
+// Records the candle timestamp, returning true if the candle was already processed
+pub(crate) fn is_stale(last_timestamp: &mut Option<i64>, candle: &Candle<f64>) -> bool {
+    if last_timestamp.is_some_and(|last| candle.timestamp <= last) {
+        return true;
+    }
+    *last_timestamp = Some(candle.timestamp);
+    false
+}
+
+/// Highest high and lowest low midpoint of the given candles.
+pub(crate) fn donchian_mid(candles: &[Candle<f64>]) -> f64 {
+    let high = candles
+        .iter()
+        .map(|c| c.high)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let low = candles.iter().map(|c| c.low).fold(f64::INFINITY, f64::min);
+    (high + low) / 2.0
+}
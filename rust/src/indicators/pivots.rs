@@ -0,0 +1,264 @@
+use crate::Candle;
+
+/// Formula used to derive pivot levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PivotKind {
+    Classic,
+    Fibonacci,
+    Camarilla,
+}
+
+/// Pivot point with its resistance and support levels.
+///
+/// `r4` and `s4` are only produced by the Camarilla formula.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PivotLevels {
+    pub pivot: f64,
+    pub r1: f64,
+    pub r2: f64,
+    pub r3: f64,
+    pub r4: Option<f64>,
+    pub s1: f64,
+    pub s2: f64,
+    pub s3: f64,
+    pub s4: Option<f64>,
+}
+
+impl PivotLevels {
+    /// Computes the levels for the next session from the previous session's candle.
+    pub fn from_candle(candle: &Candle<f64>, kind: PivotKind) -> Self {
+        let (high, low, close) = (candle.high, candle.low, candle.close);
+        let pivot = (high + low + close) / 3.0;
+        let range = high - low;
+        match kind {
+            PivotKind::Classic => PivotLevels {
+                pivot,
+                r1: 2.0 * pivot - low,
+                r2: pivot + range,
+                r3: high + 2.0 * (pivot - low),
+                r4: None,
+                s1: 2.0 * pivot - high,
+                s2: pivot - range,
+                s3: low - 2.0 * (high - pivot),
+                s4: None,
+            },
+            PivotKind::Fibonacci => PivotLevels {
+                pivot,
+                r1: pivot + 0.382 * range,
+                r2: pivot + 0.618 * range,
+                r3: pivot + range,
+                r4: None,
+                s1: pivot - 0.382 * range,
+                s2: pivot - 0.618 * range,
+                s3: pivot - range,
+                s4: None,
+            },
+            PivotKind::Camarilla => PivotLevels {
+                pivot,
+                r1: close + range * 1.1 / 12.0,
+                r2: close + range * 1.1 / 6.0,
+                r3: close + range * 1.1 / 4.0,
+                r4: Some(close + range * 1.1 / 2.0),
+                s1: close - range * 1.1 / 12.0,
+                s2: close - range * 1.1 / 6.0,
+                s3: close - range * 1.1 / 4.0,
+                s4: Some(close - range * 1.1 / 2.0),
+            },
+        }
+    }
+}
+
+/// Computes the pivot levels of every daily candle from the one preceding it.
+///
+/// Each entry carries the timestamp of the session the levels apply to, so the first
+/// candle has no entry.
+pub fn pivot_points(daily: &[Candle<f64>], kind: PivotKind) -> Vec<(i64, PivotLevels)> {
+    daily
+        .windows(2)
+        .map(|pair| (pair[1].timestamp, PivotLevels::from_candle(&pair[0], kind)))
+        .collect()
+}
+
+/// Whether a swing point is a local top or bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwingKind {
+    High,
+    Low,
+}
+
+/// A local extreme of the candle series.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwingPoint {
+    pub timestamp: i64,
+    pub price: f64,
+    pub kind: SwingKind,
+}
+
+/// Finds candles whose high (or low) is strictly above (or below) the `strength`
+/// candles on each side.
+pub fn swing_points(candles: &[Candle<f64>], strength: usize) -> Vec<SwingPoint> {
+    let mut points = Vec::new();
+    if strength == 0 || candles.len() < 2 * strength + 1 {
+        return points;
+    }
+    for i in strength..candles.len() - strength {
+        let candle = &candles[i];
+        let neighbours = candles[i - strength..=i + strength]
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != strength)
+            .map(|(_, c)| c);
+        let (mut is_high, mut is_low) = (true, true);
+        for other in neighbours {
+            is_high &= candle.high > other.high;
+            is_low &= candle.low < other.low;
+        }
+        if is_high {
+            points.push(SwingPoint {
+                timestamp: candle.timestamp,
+                price: candle.high,
+                kind: SwingKind::High,
+            });
+        }
+        if is_low {
+            points.push(SwingPoint {
+                timestamp: candle.timestamp,
+                price: candle.low,
+                kind: SwingKind::Low,
+            });
+        }
+    }
+    points
+}
+
+/// Whether a level sits below or above the last close.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelKind {
+    Support,
+    Resistance,
+}
+
+/// A price zone where several swing points clustered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Level {
+    /// Average price of the swing points in the cluster.
+    pub price: f64,
+    pub kind: LevelKind,
+    /// Number of swing points in the cluster.
+    pub touches: usize,
+    /// Timestamp of the most recent swing point in the cluster.
+    pub last_timestamp: i64,
+}
+
+/// Detects support and resistance levels by clustering swing points.
+///
+/// Swing points whose prices are within `tolerance` (relative, e.g. `0.005` for 0.5%)
+/// of a cluster's average are merged into it. Levels are sorted by price.
+pub fn support_resistance(candles: &[Candle<f64>], strength: usize, tolerance: f64) -> Vec<Level> {
+    let Some(last_close) = candles.last().map(|c| c.close) else {
+        return Vec::new();
+    };
+    let mut points = swing_points(candles, strength);
+    points.sort_by(|a, b| a.price.total_cmp(&b.price));
+
+    // (price sum, touches, last timestamp)
+    let mut clusters: Vec<(f64, usize, i64)> = Vec::new();
+    for point in points {
+        match clusters.last_mut() {
+            Some((sum, touches, last))
+                if (point.price - *sum / *touches as f64).abs()
+                    <= tolerance * point.price.abs() =>
+            {
+                *sum += point.price;
+                *touches += 1;
+                *last = (*last).max(point.timestamp);
+            }
+            _ => clusters.push((point.price, 1, point.timestamp)),
+        }
+    }
+    clusters
+        .into_iter()
+        .map(|(sum, touches, last_timestamp)| {
+            let price = sum / touches as f64;
+            Level {
+                price,
+                kind: if price > last_close {
+                    LevelKind::Resistance
+                } else {
+                    LevelKind::Support
+                },
+                touches,
+                last_timestamp,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(timestamp: i64, high: f64, low: f64, close: f64) -> Candle<f64> {
+        Candle {
+            timestamp,
+            open: close,
+            high,
+            low,
+            close,
+            volume: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_classic_pivots() {
+        let levels = PivotLevels::from_candle(&candle(0, 110.0, 90.0, 100.0), PivotKind::Classic);
+        assert_eq!(levels.pivot, 100.0);
+        assert_eq!(levels.r1, 110.0);
+        assert_eq!(levels.s1, 90.0);
+        assert_eq!(levels.r2, 120.0);
+        assert_eq!(levels.s2, 80.0);
+        assert_eq!(levels.r4, None);
+    }
+
+    #[test]
+    fn test_camarilla_pivots() {
+        let levels = PivotLevels::from_candle(&candle(0, 110.0, 90.0, 100.0), PivotKind::Camarilla);
+        assert!((levels.r4.unwrap() - 111.0).abs() < 1e-9);
+        assert!((levels.s4.unwrap() - 89.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pivot_points_apply_to_next_session() {
+        let daily = [
+            candle(0, 110.0, 90.0, 100.0),
+            candle(86_400_000, 120.0, 100.0, 110.0),
+        ];
+        let pivots = pivot_points(&daily, PivotKind::Fibonacci);
+        assert_eq!(pivots.len(), 1);
+        assert_eq!(pivots[0].0, 86_400_000);
+        assert_eq!(pivots[0].1.pivot, 100.0);
+    }
+
+    #[test]
+    fn test_swing_points_and_levels() {
+        let highs = [1.0, 3.0, 1.0, 2.0, 1.0, 3.01, 1.0];
+        let candles: Vec<_> = highs
+            .iter()
+            .enumerate()
+            .map(|(i, &h)| candle(i as i64, h, h - 0.5, 1.5))
+            .collect();
+        let swings = swing_points(&candles, 1);
+        let tops: Vec<_> = swings
+            .iter()
+            .filter(|s| s.kind == SwingKind::High)
+            .map(|s| s.price)
+            .collect();
+        assert_eq!(tops, vec![3.0, 2.0, 3.01]);
+
+        let levels = support_resistance(&candles, 1, 0.01);
+        let top = levels.last().unwrap();
+        assert_eq!(top.touches, 2);
+        assert_eq!(top.kind, LevelKind::Resistance);
+        assert_eq!(top.last_timestamp, 5);
+    }
+}
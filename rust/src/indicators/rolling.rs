@@ -0,0 +1,281 @@
+use std::collections::VecDeque;
+
+/// Sliding-window view over a numeric series, created with [`RollingExt::rolling`].
+///
+/// Every aggregation returns one value per complete window, so the output has
+/// `len - window + 1` elements and the value at index `i` covers `series[i..i + window]`.
+/// A window of zero or larger than the series yields an empty output.
+#[derive(Debug, Clone, Copy)]
+pub struct Rolling<'a> {
+    series: &'a [f64],
+    window: usize,
+}
+
+/// Adds the [`rolling`](RollingExt::rolling) combinator to numeric series.
+pub trait RollingExt {
+    fn rolling(&self, window: usize) -> Rolling<'_>;
+}
+
+impl RollingExt for [f64] {
+    fn rolling(&self, window: usize) -> Rolling<'_> {
+        Rolling::new(self, window)
+    }
+}
+
+impl<'a> Rolling<'a> {
+    pub fn new(series: &'a [f64], window: usize) -> Self {
+        Rolling { series, window }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.window == 0 || self.window > self.series.len()
+    }
+
+    /// Applies `f` to every window.
+    pub fn apply<F: FnMut(&[f64]) -> f64>(&self, f: F) -> Vec<f64> {
+        let mut out = Vec::new();
+        self.apply_into(f, &mut out);
+        out
+    }
+
+    /// Like [`apply`](Self::apply), writing into `out` (cleared first) to reuse its
+    /// allocation across calls.
+    pub fn apply_into<F: FnMut(&[f64]) -> f64>(&self, f: F, out: &mut Vec<f64>) {
+        out.clear();
+        if self.is_empty() {
+            return;
+        }
+        out.extend(self.series.windows(self.window).map(f));
+    }
+
+    /// Sum of every window, computed with a compensated running total.
+    ///
+    /// A NaN or infinite value only affects the windows holding it.
+    pub fn sum(&self) -> Vec<f64> {
+        let mut out = Vec::new();
+        self.sum_into(&mut out);
+        out
+    }
+
+    /// Like [`sum`](Self::sum), writing into `out` (cleared first).
+    pub fn sum_into(&self, out: &mut Vec<f64>) {
+        out.clear();
+        if self.is_empty() {
+            return;
+        }
+        // Running total of the finite values only, so a NaN leaving the window does not
+        // poison the later ones. Windows holding a non-finite value are summed directly.
+        let mut sum = CompensatedSum::default();
+        let mut non_finite = 0usize;
+        out.reserve(self.series.len() - self.window + 1);
+        for (i, &value) in self.series.iter().enumerate() {
+            if value.is_finite() {
+                sum.add(value);
+            } else {
+                non_finite += 1;
+            }
+            if i >= self.window {
+                let old = self.series[i - self.window];
+                if old.is_finite() {
+                    sum.add(-old);
+                } else {
+                    non_finite -= 1;
+                }
+            }
+            if i + 1 >= self.window {
+                out.push(if non_finite == 0 {
+                    sum.value()
+                } else {
+                    self.series[i + 1 - self.window..=i].iter().sum()
+                });
+            }
+        }
+    }
+
+    /// Arithmetic mean of every window.
+    pub fn mean(&self) -> Vec<f64> {
+        let mut out = Vec::new();
+        self.mean_into(&mut out);
+        out
+    }
+
+    /// Like [`mean`](Self::mean), writing into `out` (cleared first).
+    pub fn mean_into(&self, out: &mut Vec<f64>) {
+        let window = self.window as f64;
+        self.sum_into(out);
+        out.iter_mut().for_each(|sum| *sum /= window);
+    }
+
+    /// Sample standard deviation of every window (`n - 1` denominator).
+    ///
+    /// A window of one yields zeros.
+    pub fn std(&self) -> Vec<f64> {
+        let mut out = Vec::new();
+        self.std_into(&mut out);
+        out
+    }
+
+    /// Like [`std`](Self::std), writing into `out` (cleared first).
+    pub fn std_into(&self, out: &mut Vec<f64>) {
+        if self.window < 2 {
+            self.sum_into(out);
+            out.iter_mut().for_each(|v| *v = 0.0);
+            return;
+        }
+        self.apply_into(
+            |values| {
+                let mean = values.iter().sum::<f64>() / values.len() as f64;
+                let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>()
+                    / (values.len() - 1) as f64;
+                var.sqrt()
+            },
+            out,
+        )
+    }
+
+    /// Minimum of every window.
+    pub fn min(&self) -> Vec<f64> {
+        let mut out = Vec::new();
+        self.min_into(&mut out);
+        out
+    }
+
+    /// Like [`min`](Self::min), writing into `out` (cleared first).
+    pub fn min_into(&self, out: &mut Vec<f64>) {
+        self.extreme_into(|candidate, kept| candidate <= kept, out)
+    }
+
+    /// Maximum of every window.
+    pub fn max(&self) -> Vec<f64> {
+        let mut out = Vec::new();
+        self.max_into(&mut out);
+        out
+    }
+
+    /// Like [`max`](Self::max), writing into `out` (cleared first).
+    pub fn max_into(&self, out: &mut Vec<f64>) {
+        self.extreme_into(|candidate, kept| candidate >= kept, out)
+    }
+
+    // Monotonic deque: indexes whose values are dominated by a newer value are dropped
+    fn extreme_into(&self, dominates: impl Fn(f64, f64) -> bool, out: &mut Vec<f64>) {
+        out.clear();
+        if self.is_empty() {
+            return;
+        }
+        let mut deque: VecDeque<usize> = VecDeque::with_capacity(self.window);
+        out.reserve(self.series.len() - self.window + 1);
+        for (i, &value) in self.series.iter().enumerate() {
+            while deque
+                .back()
+                .is_some_and(|&j| dominates(value, self.series[j]))
+            {
+                deque.pop_back();
+            }
+            deque.push_back(i);
+            if deque[0] + self.window <= i {
+                deque.pop_front();
+            }
+            if i + 1 >= self.window {
+                out.push(self.series[deque[0]]);
+            }
+        }
+    }
+}
+
+// Neumaier summation: keeps the low-order bits lost by each addition, so a long running
+// total does not drift
+#[derive(Default)]
+struct CompensatedSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl CompensatedSum {
+    fn add(&mut self, value: f64) {
+        let sum = self.sum + value;
+        self.compensation += if self.sum.abs() >= value.abs() {
+            (self.sum - sum) + value
+        } else {
+            (value - sum) + self.sum
+        };
+        self.sum = sum;
+    }
+
+    fn value(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERIES: [f64; 6] = [1.0, 3.0, 2.0, 5.0, 4.0, 0.0];
+
+    #[test]
+    fn test_rolling_mean() {
+        assert_eq!(
+            SERIES.rolling(3).mean(),
+            vec![2.0, 10.0 / 3.0, 11.0 / 3.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn test_rolling_std() {
+        let std = SERIES.rolling(2).std();
+        assert_eq!(std.len(), 5);
+        assert!((std[0] - 2.0_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(SERIES.rolling(1).std(), vec![0.0; 6]);
+    }
+
+    #[test]
+    fn test_rolling_min_max() {
+        assert_eq!(SERIES.rolling(3).min(), vec![1.0, 2.0, 2.0, 0.0]);
+        assert_eq!(SERIES.rolling(3).max(), vec![3.0, 5.0, 5.0, 5.0]);
+    }
+
+    #[test]
+    fn test_rolling_apply() {
+        let ranges = SERIES.rolling(2).apply(|w| w[1] - w[0]);
+        assert_eq!(ranges, vec![2.0, -1.0, 3.0, -1.0, -4.0]);
+    }
+
+    #[test]
+    fn test_rolling_sum_nan_stays_local() {
+        let series = [1.0, 2.0, f64::NAN, 4.0, 5.0, 6.0];
+        let sums = series.rolling(2).sum();
+        assert_eq!(sums.len(), 5);
+        assert_eq!(sums[0], 3.0);
+        assert!(sums[1].is_nan() && sums[2].is_nan());
+        assert_eq!(&sums[3..], &[9.0, 11.0]);
+        assert_eq!(series.rolling(2).mean()[4], 5.5);
+    }
+
+    #[test]
+    fn test_rolling_sum_does_not_drift() {
+        // A plain running total loses the small values next to the spike
+        let series = [1e20, 1.0, 1.0, 1.0, 0.1, 0.2];
+        assert_eq!(series.rolling(2).sum()[1..], [2.0, 2.0, 1.1, 0.1 + 0.2]);
+    }
+
+    #[test]
+    fn test_rolling_invalid_window() {
+        assert!(SERIES.rolling(0).mean().is_empty());
+        assert!(SERIES.rolling(7).max().is_empty());
+        assert!(SERIES.rolling(7).std().is_empty());
+    }
+
+    #[test]
+    fn test_into_reuses_buffer() {
+        let mut out = vec![42.0; 10];
+        SERIES.rolling(2).mean_into(&mut out);
+        assert_eq!(out, SERIES.rolling(2).mean());
+        SERIES.rolling(3).max_into(&mut out);
+        assert_eq!(out, SERIES.rolling(3).max());
+        SERIES.rolling(1).std_into(&mut out);
+        assert_eq!(out, vec![0.0; 6]);
+        SERIES.rolling(7).sum_into(&mut out);
+        assert!(out.is_empty());
+    }
+}
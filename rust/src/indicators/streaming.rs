@@ -0,0 +1,452 @@
+use extism_pdk::{Error, WithReturnCode};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{Indicator, is_stale};
+use crate::Candle;
+
+/// Serializes an indicator state so it can be emitted as piped data and restored
+/// on the next invocation with [`load_state`].
+pub fn save_state<T: Serialize>(state: &T) -> Result<String, WithReturnCode<Error>> {
+    serde_json::to_string(state).map_err(|e| {
+        WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "Failed to serialize indicator state: {}",
+                e
+            ))),
+            8,
+        )
+    })
+}
+
+/// Restores an indicator state previously produced by [`save_state`].
+pub fn load_state<T: DeserializeOwned>(data: &str) -> Result<T, WithReturnCode<Error>> {
+    serde_json::from_str(data).map_err(|e| {
+        WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "Failed to parse indicator state: {}",
+                e
+            ))),
+            8,
+        )
+    })
+}
+
+/// Exponential moving average, seeded with the simple average of the first `period` values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ema {
+    period: usize,
+    value: Option<f64>,
+    seed_sum: f64,
+    seen: usize,
+    last_timestamp: Option<i64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        Ema {
+            period: period.max(1),
+            value: None,
+            seed_sum: 0.0,
+            seen: 0,
+            last_timestamp: None,
+        }
+    }
+
+    /// The current value, `None` until `period` values have been seen.
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    /// Feeds a raw value and returns the updated average.
+    pub fn update_value(&mut self, value: f64) -> Option<f64> {
+        self.value = match self.value {
+            Some(prev) => {
+                let alpha = 2.0 / (self.period as f64 + 1.0);
+                Some(prev + alpha * (value - prev))
+            }
+            None => {
+                self.seed_sum += value;
+                self.seen += 1;
+                (self.seen == self.period).then(|| self.seed_sum / self.period as f64)
+            }
+        };
+        self.value
+    }
+
+    /// Feeds every raw value in order and returns the average after each of them.
+    pub fn update_values(&mut self, values: &[f64]) -> Vec<Option<f64>> {
+        let mut out = Vec::new();
+        self.update_values_into(values, &mut out);
+        out
+    }
+
+    /// Like [`update_values`](Self::update_values), writing into `out` (cleared first)
+    /// to reuse its allocation across calls.
+    pub fn update_values_into(&mut self, values: &[f64], out: &mut Vec<Option<f64>>) {
+        out.clear();
+        out.extend(values.iter().map(|&value| self.update_value(value)));
+    }
+}
+
+/// Feeds the close of each candle, ignoring candles already processed by this state.
+impl Indicator for Ema {
+    type Output = f64;
+
+    fn update(&mut self, candle: &Candle<f64>) -> Option<f64> {
+        if is_stale(&mut self.last_timestamp, candle) {
+            return self.value;
+        }
+        self.update_value(candle.close)
+    }
+
+    fn reset(&mut self) {
+        *self = Ema::new(self.period);
+    }
+
+    fn warmup_len(&self) -> usize {
+        self.period
+    }
+}
+
+/// Relative strength index using Wilder's smoothing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rsi {
+    period: usize,
+    prev_close: Option<f64>,
+    avg_gain: f64,
+    avg_loss: f64,
+    seen: usize,
+    last_timestamp: Option<i64>,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        Rsi {
+            period: period.max(1),
+            prev_close: None,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+            seen: 0,
+            last_timestamp: None,
+        }
+    }
+
+    /// The current value in `[0, 100]`, `None` until `period` price changes have been seen.
+    pub fn value(&self) -> Option<f64> {
+        if self.seen < self.period {
+            return None;
+        }
+        if self.avg_loss == 0.0 {
+            return Some(if self.avg_gain == 0.0 { 50.0 } else { 100.0 });
+        }
+        let rs = self.avg_gain / self.avg_loss;
+        Some(100.0 - 100.0 / (1.0 + rs))
+    }
+
+    /// Feeds a raw close and returns the updated RSI.
+    pub fn update_value(&mut self, close: f64) -> Option<f64> {
+        if let Some(prev) = self.prev_close.replace(close) {
+            let change = close - prev;
+            let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+            let period = self.period as f64;
+            if self.seen < self.period {
+                // Seed with the simple average of the first `period` changes
+                self.seen += 1;
+                self.avg_gain += gain / period;
+                self.avg_loss += loss / period;
+            } else {
+                self.avg_gain = (self.avg_gain * (period - 1.0) + gain) / period;
+                self.avg_loss = (self.avg_loss * (period - 1.0) + loss) / period;
+            }
+        }
+        self.value()
+    }
+
+    /// Feeds every raw close in order and returns the RSI after each of them.
+    pub fn update_values(&mut self, closes: &[f64]) -> Vec<Option<f64>> {
+        let mut out = Vec::new();
+        self.update_values_into(closes, &mut out);
+        out
+    }
+
+    /// Like [`update_values`](Self::update_values), writing into `out` (cleared first)
+    /// to reuse its allocation across calls.
+    pub fn update_values_into(&mut self, closes: &[f64], out: &mut Vec<Option<f64>>) {
+        out.clear();
+        out.extend(closes.iter().map(|&close| self.update_value(close)));
+    }
+}
+
+/// Feeds the close of each candle, ignoring candles already processed by this state.
+impl Indicator for Rsi {
+    type Output = f64;
+
+    fn update(&mut self, candle: &Candle<f64>) -> Option<f64> {
+        if is_stale(&mut self.last_timestamp, candle) {
+            return self.value();
+        }
+        self.update_value(candle.close)
+    }
+
+    fn reset(&mut self) {
+        *self = Rsi::new(self.period);
+    }
+
+    fn warmup_len(&self) -> usize {
+        self.period + 1
+    }
+}
+
+/// Average true range using Wilder's smoothing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Atr {
+    period: usize,
+    prev_close: Option<f64>,
+    value: Option<f64>,
+    seed_sum: f64,
+    seen: usize,
+    last_timestamp: Option<i64>,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        Atr {
+            period: period.max(1),
+            prev_close: None,
+            value: None,
+            seed_sum: 0.0,
+            seen: 0,
+            last_timestamp: None,
+        }
+    }
+
+    /// The current value, `None` until `period` candles have been seen.
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+/// Ignores candles already processed by this state.
+impl Indicator for Atr {
+    type Output = f64;
+
+    fn update(&mut self, candle: &Candle<f64>) -> Option<f64> {
+        if is_stale(&mut self.last_timestamp, candle) {
+            return self.value;
+        }
+        let true_range = match self.prev_close.replace(candle.close) {
+            Some(prev) => (candle.high - candle.low)
+                .max((candle.high - prev).abs())
+                .max((candle.low - prev).abs()),
+            None => candle.high - candle.low,
+        };
+        let period = self.period as f64;
+        self.value = match self.value {
+            Some(prev) => Some((prev * (period - 1.0) + true_range) / period),
+            None => {
+                self.seed_sum += true_range;
+                self.seen += 1;
+                (self.seen == self.period).then(|| self.seed_sum / period)
+            }
+        };
+        self.value
+    }
+
+    fn reset(&mut self) {
+        *self = Atr::new(self.period);
+    }
+
+    fn warmup_len(&self) -> usize {
+        self.period
+    }
+}
+
+/// Feeds an indicator with a bar that is revised while it forms, as pushed by a
+/// [`Stream`](crate::stream::Stream).
+///
+/// The built-in indicators ignore a candle whose timestamp they already processed, so
+/// they would keep the first version of a forming bar. This wrapper keeps the state from
+/// before the bar and replays each new version of it on a copy of that state instead.
+///
+/// ```ignore
+/// let mut ema = FormingBar::new(Ema::new(20));
+/// ema.update(&partial);
+/// let value = ema.update(&closed); // as if only `closed` had been fed
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormingBar<I> {
+    // State before the last bar
+    before: I,
+    current: I,
+    timestamp: Option<i64>,
+}
+
+impl<I: Indicator + Clone> FormingBar<I> {
+    pub fn new(indicator: I) -> Self {
+        FormingBar {
+            before: indicator.clone(),
+            current: indicator,
+            timestamp: None,
+        }
+    }
+
+    /// The wrapped indicator, including the last version of the forming bar.
+    pub fn inner(&self) -> &I {
+        &self.current
+    }
+}
+
+impl<I: Indicator + Clone> Indicator for FormingBar<I> {
+    type Output = I::Output;
+
+    fn update(&mut self, candle: &Candle<f64>) -> Option<I::Output> {
+        match self.timestamp {
+            Some(timestamp) if candle.timestamp == timestamp => {
+                self.current.clone_from(&self.before)
+            }
+            // Older candles are ignored by the wrapped indicator
+            Some(timestamp) if candle.timestamp < timestamp => {}
+            _ => {
+                self.before.clone_from(&self.current);
+                self.timestamp = Some(candle.timestamp);
+            }
+        }
+        self.current.update(candle)
+    }
+
+    fn reset(&mut self) {
+        self.current.reset();
+        self.before.reset();
+        self.timestamp = None;
+    }
+
+    fn warmup_len(&self) -> usize {
+        self.current.warmup_len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(timestamp: i64, close: f64) -> Candle<f64> {
+        Candle {
+            timestamp,
+            open: close,
+            high: close + 1.0,
+            low: close - 1.0,
+            close,
+            volume: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_ema_seed_and_update() {
+        let mut ema = Ema::new(3);
+        assert_eq!(ema.update_value(1.0), None);
+        assert_eq!(ema.update_value(2.0), None);
+        assert_eq!(ema.update_value(3.0), Some(2.0));
+        assert_eq!(ema.update_value(4.0), Some(3.0));
+    }
+
+    #[test]
+    fn test_rsi_bounds() {
+        let mut rsi = Rsi::new(3);
+        for close in [1.0, 2.0, 3.0] {
+            assert_eq!(rsi.update_value(close), None);
+        }
+        assert_eq!(rsi.update_value(4.0), Some(100.0));
+        let value = rsi.update_value(3.0).unwrap();
+        assert!(value > 0.0 && value < 100.0);
+    }
+
+    #[test]
+    fn test_atr_skips_processed_candles() {
+        let mut atr = Atr::new(2);
+        atr.update(&candle(1, 10.0));
+        let value = atr.update(&candle(2, 10.0));
+        assert_eq!(value, Some(2.0));
+        // Replaying the same candles does not change the state
+        let before = atr.clone();
+        atr.update(&candle(1, 50.0));
+        atr.update(&candle(2, 50.0));
+        assert_eq!(atr, before);
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let candles: Vec<_> = (0..10).map(|i| candle(i, (i * i) as f64)).collect();
+        let mut full = Rsi::new(4);
+        let mut first = Rsi::new(4);
+        for c in &candles {
+            full.update(c);
+        }
+        for c in &candles[..6] {
+            first.update(c);
+        }
+        let mut restored: Rsi = load_state(&save_state(&first).unwrap()).unwrap();
+        // The next invocation receives overlapping history
+        for c in &candles[3..] {
+            restored.update(c);
+        }
+        assert_eq!(restored, full);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut ema = Ema::new(2);
+        ema.compute(&[candle(1, 1.0), candle(2, 2.0)]);
+        ema.reset();
+        assert_eq!(ema, Ema::new(2));
+    }
+
+    #[test]
+    fn test_compute_chunks_matches_compute() {
+        let candles: Vec<_> = (0..10).map(|i| candle(i, (i * i) as f64)).collect();
+        let expected = Rsi::new(4).compute(&candles);
+        let mut chunked = Vec::new();
+        Rsi::new(4).compute_chunks(&candles, 3, |chunk, values| {
+            assert_eq!(chunk.len(), values.len());
+            chunked.extend_from_slice(values);
+        });
+        assert_eq!(chunked, expected);
+    }
+
+    #[test]
+    fn test_into_reuses_buffer() {
+        let candles: Vec<_> = (0..10).map(|i| candle(i, (i * i) as f64)).collect();
+        let closes: Vec<f64> = candles.iter().map(|c| c.close).collect();
+        let mut out = Vec::with_capacity(candles.len());
+        let buffer = out.as_ptr();
+
+        Ema::new(3).update_values_into(&closes, &mut out);
+        assert_eq!(out, Ema::new(3).compute(&candles));
+        Rsi::new(3).update_values_into(&closes, &mut out);
+        assert_eq!(out, Rsi::new(3).compute(&candles));
+        Atr::new(3).compute_into(&candles, &mut out);
+        assert_eq!(out, Atr::new(3).compute(&candles));
+        assert_eq!(out.as_ptr(), buffer);
+    }
+
+    #[test]
+    fn test_forming_bar_replays_revisions() {
+        let mut plain = Ema::new(2);
+        let mut forming = FormingBar::new(Ema::new(2));
+        for indicator in [&mut plain as &mut dyn Indicator<Output = f64>, &mut forming] {
+            indicator.update(&candle(1, 10.0));
+            indicator.update(&candle(2, 12.0));
+            indicator.update(&candle(2, 20.0));
+        }
+        assert_eq!(plain.value(), Some(11.0));
+        assert_eq!(forming.inner().value(), Some(15.0));
+        assert_eq!(forming.update(&candle(1, 50.0)), Some(15.0));
+
+        let closed = Ema::new(2).compute(&[candle(1, 10.0), candle(2, 20.0), candle(3, 30.0)]);
+        assert_eq!(forming.update(&candle(3, 30.0)), closed[2]);
+    }
+
+    #[test]
+    fn test_load_invalid_state() {
+        assert!(load_state::<Ema>("not json").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::Candle;
+
+/// Scales a per-candle volatility to a yearly one, e.g. with `periods_per_year` of
+/// `365.0 * 24.0` for hourly crypto candles.
+pub fn annualize(volatility: f64, periods_per_year: f64) -> f64 {
+    volatility * periods_per_year.sqrt()
+}
+
+/// Parkinson volatility per candle, estimated from the high-low range.
+///
+/// Returns `None` for an empty series.
+pub fn parkinson(candles: &[Candle<f64>]) -> Option<f64> {
+    if candles.is_empty() {
+        return None;
+    }
+    let sum: f64 = candles.iter().map(|c| (c.high / c.low).ln().powi(2)).sum();
+    Some((sum / (4.0 * candles.len() as f64 * std::f64::consts::LN_2)).sqrt())
+}
+
+/// Garman-Klass volatility per candle, estimated from the open, high, low and close.
+///
+/// Returns `None` for an empty series.
+pub fn garman_klass(candles: &[Candle<f64>]) -> Option<f64> {
+    if candles.is_empty() {
+        return None;
+    }
+    let k = 2.0 * std::f64::consts::LN_2 - 1.0;
+    let sum: f64 = candles
+        .iter()
+        .map(|c| 0.5 * (c.high / c.low).ln().powi(2) - k * (c.close / c.open).ln().powi(2))
+        .sum();
+    Some((sum / candles.len() as f64).max(0.0).sqrt())
+}
+
+/// Yang-Zhang volatility per candle, combining the overnight (close-to-open) jumps with
+/// the Rogers-Satchell intraperiod estimator.
+///
+/// The first candle only provides the previous close, so at least three candles are
+/// needed; returns `None` otherwise.
+pub fn yang_zhang(candles: &[Candle<f64>]) -> Option<f64> {
+    if candles.len() < 3 {
+        return None;
+    }
+    let n = (candles.len() - 1) as f64;
+    let pairs = candles.windows(2);
+    let overnight: Vec<f64> = pairs
+        .clone()
+        .map(|w| (w[1].open / w[0].close).ln())
+        .collect();
+    let open_close: Vec<f64> = candles[1..]
+        .iter()
+        .map(|c| (c.close / c.open).ln())
+        .collect();
+    let rogers_satchell: f64 = candles[1..]
+        .iter()
+        .map(|c| {
+            (c.high / c.close).ln() * (c.high / c.open).ln()
+                + (c.low / c.close).ln() * (c.low / c.open).ln()
+        })
+        .sum::<f64>()
+        / n;
+    let variance = |values: &[f64]| {
+        let mean = values.iter().sum::<f64>() / n;
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+    };
+    let k = 0.34 / (1.34 + (n + 1.0) / (n - 1.0));
+    let total = variance(&overnight) + k * variance(&open_close) + (1.0 - k) * rogers_satchell;
+    Some(total.max(0.0).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(open: f64, high: f64, low: f64, close: f64) -> Candle<f64> {
+        Candle {
+            timestamp: 0,
+            open,
+            high,
+            low,
+            close,
+            volume: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_flat_candles_have_no_volatility() {
+        let candles: Vec<_> = (0..5).map(|_| candle(10.0, 10.0, 10.0, 10.0)).collect();
+        assert_eq!(parkinson(&candles), Some(0.0));
+        assert_eq!(garman_klass(&candles), Some(0.0));
+        assert_eq!(yang_zhang(&candles), Some(0.0));
+    }
+
+    #[test]
+    fn test_parkinson() {
+        let e = std::f64::consts::E;
+        let candles = [candle(1.0, e, 1.0, 1.0)];
+        let expected = (1.0 / (4.0 * std::f64::consts::LN_2)).sqrt();
+        assert!((parkinson(&candles).unwrap() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_estimators_are_positive() {
+        let candles = [
+            candle(100.0, 105.0, 98.0, 103.0),
+            candle(104.0, 108.0, 101.0, 102.0),
+            candle(101.0, 103.0, 95.0, 96.0),
+            candle(97.0, 100.0, 94.0, 99.0),
+        ];
+        assert!(garman_klass(&candles).unwrap() > 0.0);
+        assert!(yang_zhang(&candles).unwrap() > 0.0);
+        assert_eq!(yang_zhang(&candles[..2]), None);
+    }
+
+    #[test]
+    fn test_annualize() {
+        assert_eq!(annualize(0.01, 10_000.0), 1.0);
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Indicator, is_stale};
+use crate::Candle;
+
+/// On-balance volume: the running sum of volume, added on up closes and subtracted on
+/// down closes. Starts at zero on the first candle.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Obv {
+    value: f64,
+    prev_close: Option<f64>,
+    last_timestamp: Option<i64>,
+}
+
+impl Obv {
+    pub fn new() -> Self {
+        Obv::default()
+    }
+}
+
+impl Indicator for Obv {
+    type Output = f64;
+
+    fn update(&mut self, candle: &Candle<f64>) -> Option<f64> {
+        if is_stale(&mut self.last_timestamp, candle) {
+            return Some(self.value);
+        }
+        if let Some(prev) = self.prev_close.replace(candle.close) {
+            if candle.close > prev {
+                self.value += candle.volume;
+            } else if candle.close < prev {
+                self.value -= candle.volume;
+            }
+        }
+        Some(self.value)
+    }
+
+    fn reset(&mut self) {
+        *self = Obv::new();
+    }
+
+    fn warmup_len(&self) -> usize {
+        1
+    }
+}
+
+/// Money flow index: a volume-weighted RSI over the typical price `(high + low + close) / 3`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mfi {
+    period: usize,
+    prev_typical: Option<f64>,
+    // (positive flow, negative flow) of the last `period` candles
+    flows: VecDeque<(f64, f64)>,
+    last_timestamp: Option<i64>,
+}
+
+impl Mfi {
+    pub fn new(period: usize) -> Self {
+        Mfi {
+            period: period.max(1),
+            prev_typical: None,
+            flows: VecDeque::with_capacity(period.max(1)),
+            last_timestamp: None,
+        }
+    }
+
+    /// The current value in `[0, 100]`, `None` until `period` price changes have been seen.
+    pub fn value(&self) -> Option<f64> {
+        if self.flows.len() < self.period {
+            return None;
+        }
+        let (positive, negative) = self
+            .flows
+            .iter()
+            .fold((0.0, 0.0), |(p, n), &(fp, fn_)| (p + fp, n + fn_));
+        if negative == 0.0 {
+            return Some(if positive == 0.0 { 50.0 } else { 100.0 });
+        }
+        Some(100.0 - 100.0 / (1.0 + positive / negative))
+    }
+}
+
+impl Indicator for Mfi {
+    type Output = f64;
+
+    fn update(&mut self, candle: &Candle<f64>) -> Option<f64> {
+        if is_stale(&mut self.last_timestamp, candle) {
+            return self.value();
+        }
+        let typical = (candle.high + candle.low + candle.close) / 3.0;
+        if let Some(prev) = self.prev_typical.replace(typical) {
+            let flow = typical * candle.volume;
+            let entry = if typical > prev {
+                (flow, 0.0)
+            } else if typical < prev {
+                (0.0, flow)
+            } else {
+                (0.0, 0.0)
+            };
+            if self.flows.len() == self.period {
+                self.flows.pop_front();
+            }
+            self.flows.push_back(entry);
+        }
+        self.value()
+    }
+
+    // Keeps the allocation of the flows
+    fn reset(&mut self) {
+        self.prev_typical = None;
+        self.flows.clear();
+        self.last_timestamp = None;
+    }
+
+    fn warmup_len(&self) -> usize {
+        self.period + 1
+    }
+}
+
+/// A price range of a [`VolumeProfile`] and the volume traded within it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeBucket {
+    pub low: f64,
+    pub high: f64,
+    pub volume: f64,
+}
+
+/// Histogram of traded volume by price.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolumeProfile {
+    /// Equal-width buckets from the lowest low to the highest high, in ascending price order.
+    pub buckets: Vec<VolumeBucket>,
+}
+
+impl VolumeProfile {
+    /// Builds a profile with `buckets` price buckets.
+    ///
+    /// Each candle's volume is spread uniformly over its high-low range, so a bucket
+    /// receives the share of the volume matching its overlap with the candle.
+    pub fn compute(candles: &[Candle<f64>], buckets: usize) -> Self {
+        let low = candles.iter().map(|c| c.low).fold(f64::INFINITY, f64::min);
+        let high = candles
+            .iter()
+            .map(|c| c.high)
+            .fold(f64::NEG_INFINITY, f64::max);
+        if buckets == 0 || candles.is_empty() || !low.is_finite() || !high.is_finite() {
+            return VolumeProfile {
+                buckets: Vec::new(),
+            };
+        }
+        let width = (high - low) / buckets as f64;
+        let mut profile: Vec<VolumeBucket> = (0..buckets)
+            .map(|i| VolumeBucket {
+                low: low + width * i as f64,
+                high: low + width * (i + 1) as f64,
+                volume: 0.0,
+            })
+            .collect();
+        let bucket_of = |price: f64| {
+            if width == 0.0 {
+                0
+            } else {
+                (((price - low) / width) as usize).min(buckets - 1)
+            }
+        };
+        for candle in candles {
+            let range = candle.high - candle.low;
+            if range <= 0.0 {
+                profile[bucket_of(candle.close)].volume += candle.volume;
+                continue;
+            }
+            for bucket in &mut profile[bucket_of(candle.low)..=bucket_of(candle.high)] {
+                let overlap = candle.high.min(bucket.high) - candle.low.max(bucket.low);
+                bucket.volume += candle.volume * overlap.max(0.0) / range;
+            }
+        }
+        VolumeProfile { buckets: profile }
+    }
+
+    /// The bucket with the most traded volume.
+    pub fn point_of_control(&self) -> Option<&VolumeBucket> {
+        self.buckets
+            .iter()
+            .max_by(|a, b| a.volume.total_cmp(&b.volume))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(timestamp: i64, low: f64, high: f64, close: f64, volume: f64) -> Candle<f64> {
+        Candle {
+            timestamp,
+            open: close,
+            high,
+            low,
+            close,
+            volume,
+        }
+    }
+
+    #[test]
+    fn test_obv() {
+        let candles = [
+            candle(1, 9.0, 11.0, 10.0, 5.0),
+            candle(2, 10.0, 12.0, 11.0, 3.0),
+            candle(3, 9.0, 11.0, 10.0, 2.0),
+            candle(4, 9.0, 11.0, 10.0, 7.0),
+        ];
+        let values = Obv::new().compute(&candles);
+        assert_eq!(values, vec![Some(0.0), Some(3.0), Some(1.0), Some(1.0)]);
+    }
+
+    #[test]
+    fn test_mfi() {
+        let mut mfi = Mfi::new(2);
+        assert_eq!(mfi.update(&candle(1, 9.0, 11.0, 10.0, 1.0)), None);
+        assert_eq!(mfi.update(&candle(2, 10.0, 12.0, 11.0, 1.0)), None);
+        assert_eq!(mfi.update(&candle(3, 11.0, 13.0, 12.0, 1.0)), Some(100.0));
+        let value = mfi.update(&candle(4, 9.0, 11.0, 10.0, 1.0)).unwrap();
+        // Positive flow 12 from the third candle, negative flow 10 from the fourth
+        assert!((value - (100.0 - 100.0 / (1.0 + 12.0 / 10.0))).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mfi_reset() {
+        let candles: Vec<_> = (0..10)
+            .map(|i| candle(i, 9.0, 11.0 + (i % 3) as f64, 10.0, 1.0))
+            .collect();
+        let mut mfi = Mfi::new(3);
+        let mut out = Vec::new();
+        mfi.compute_into(&candles, &mut out);
+        mfi.reset();
+        assert_eq!(mfi, Mfi::new(3));
+        let buffer = out.as_ptr();
+        mfi.compute_into(&candles, &mut out);
+        assert_eq!(out, Mfi::new(3).compute(&candles));
+        assert_eq!(out.as_ptr(), buffer);
+    }
+
+    #[test]
+    fn test_volume_profile() {
+        let candles = [
+            candle(1, 0.0, 10.0, 5.0, 10.0),
+            candle(2, 0.0, 5.0, 2.0, 4.0),
+            candle(3, 7.0, 7.0, 7.0, 1.0),
+        ];
+        let profile = VolumeProfile::compute(&candles, 2);
+        assert_eq!(profile.buckets.len(), 2);
+        assert_eq!(profile.buckets[0].volume, 9.0);
+        assert_eq!(profile.buckets[1].volume, 6.0);
+        assert_eq!(profile.point_of_control().unwrap().low, 0.0);
+        let total: f64 = profile.buckets.iter().map(|b| b.volume).sum();
+        assert_eq!(total, 15.0);
+    }
+
+    #[test]
+    fn test_volume_profile_empty() {
+        assert!(VolumeProfile::compute(&[], 10).buckets.is_empty());
+    }
+}
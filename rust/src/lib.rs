@@ -1,8 +1,133 @@
+// Lets the code generated by the macros refer to this crate by name from within it
+extern crate self as exchange_outpost_abi;
+
+#[cfg(feature = "analytics")]
+pub mod backtest;
+#[cfg(feature = "binary")]
+pub mod binary;
+#[cfg(feature = "calendar")]
+pub mod calendar;
+mod call;
 mod candle;
+#[cfg(feature = "decimal")]
+mod decimal_string;
+mod error;
+#[cfg(feature = "calendar")]
+mod events;
+mod execution;
+mod export;
 mod fin_data;
+#[cfg(feature = "decimal")]
+mod fx;
+#[cfg(feature = "history")]
+mod history;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "indicators")]
+pub mod indicators;
+mod intern;
+#[cfg(feature = "logging")]
+pub mod logging;
+#[cfg(feature = "decimal")]
+mod market;
+#[cfg(feature = "news")]
+pub mod news;
+#[cfg(feature = "notifications")]
 mod notifications;
+#[cfg(feature = "options")]
+pub mod options;
+#[cfg(feature = "decimal")]
+pub mod orders;
+mod output;
+mod pipe;
+#[cfg(feature = "decimal")]
+mod portfolio;
+pub mod prelude;
+#[cfg(feature = "random")]
+pub mod random;
+#[cfg(feature = "state")]
+mod rate_limit;
+#[cfg(all(feature = "runner", not(target_arch = "wasm32")))]
+pub mod runner;
+mod secrets;
+mod session;
+#[cfg(feature = "decimal")]
+pub mod sizing;
+#[cfg(feature = "test-utils")]
+pub mod snapshot;
+#[cfg(feature = "state")]
+pub mod state;
+#[cfg(feature = "analytics")]
+pub mod stats;
+#[cfg(all(feature = "proptest", not(target_arch = "wasm32")))]
+pub mod strategies;
+#[cfg(feature = "streaming")]
+pub mod stream;
+#[cfg(feature = "random")]
+pub mod testgen;
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+pub mod testing;
+mod timeframe;
+#[cfg(feature = "trading")]
+pub mod trading;
+
+pub use call::{PipePayload, call_function};
+pub use candle::{Candle, TimestampUnit, normalize_timestamp};
+#[cfg(feature = "decimal")]
+pub use decimal_string::DecimalString;
+pub use error::{AbiError, ResultExt};
+#[cfg(feature = "calendar")]
+pub use events::{Event, EventKind, Impact};
+#[cfg(feature = "macros")]
+pub use exchange_outpost_abi_macros::{PipeOutput, exchange_function};
+pub use execution::{ExecutionContext, ExecutionMode, execution_context, now};
+pub use fin_data::{AlignedCloses, FunctionArgs, TickersData};
+#[cfg(feature = "decimal")]
+pub use fx::{Currency, FxRates, Money};
+#[cfg(feature = "history")]
+pub use history::fetch_candles;
+pub use intern::Symbol;
+#[cfg(feature = "decimal")]
+pub use market::MarketInfo;
+#[cfg(feature = "notifications")]
+pub use notifications::{schedule_email, schedule_webhook};
+pub use output::FunctionOutput;
+pub use pipe::PipeOutput;
+#[cfg(feature = "decimal")]
+pub use portfolio::{Holding, Portfolio};
+#[cfg(feature = "state")]
+pub use rate_limit::{Rate, RateLimiter};
+pub use secrets::{Secret, get_secret};
+pub use session::{DstRule, Session, TimeZone};
+pub use timeframe::{ParseTimeframeError, Timeframe, TimeframeUnit};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::export::export;
+    #[cfg(feature = "streaming")]
+    pub use crate::export::{stream_init, stream_push};
+}
+
+/// Exports the `stream_init` and `stream_push` entry points of the streaming mode for a
+/// [`StreamFunction`](crate::stream::StreamFunction).
+///
+/// ```ignore
+/// exchange_outpost_abi::export_stream!(Breakout);
+/// ```
+#[cfg(feature = "streaming")]
+#[macro_export]
+macro_rules! export_stream {
+    ($function:ty) => {
+        #[cfg(target_arch = "wasm32")]
+        #[unsafe(export_name = "stream_init")]
+        pub extern "C" fn __exchange_stream_init() -> i32 {
+            $crate::__private::stream_init::<$function>()
+        }
 
-pub use candle::Candle;
-pub use fin_data::FunctionArgs;
-pub use notifications::schedule_email;
-pub use notifications::schedule_webhook;
\ No newline at end of file
+        #[cfg(target_arch = "wasm32")]
+        #[unsafe(export_name = "stream_push")]
+        pub extern "C" fn __exchange_stream_push() -> i32 {
+            $crate::__private::stream_push::<$function>()
+        }
+    };
+}
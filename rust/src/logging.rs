@@ -0,0 +1,208 @@
+//! Logging to the platform's execution logs.
+//!
+//! Messages go through the Extism host logging functions, which the platform attaches to
+//! the execution of the function. Structured fields are appended to the message in
+//! `key=value` form so they can be searched in the logs.
+//!
+//! Once [`init`] is called, the macros of the [`log`] facade are forwarded the same way,
+//! their key-value pairs becoming fields:
+//!
+//! ```ignore
+//! exchange_outpost_abi::logging::init();
+//! log::info!(symbol = "BTCUSDT", price = 50_000.5; "signal triggered");
+//! ```
+use std::fmt::Display;
+
+use log::kv::{self, VisitSource};
+
+pub use extism_pdk::LogLevel;
+
+#[cfg(any(not(feature = "test-utils"), target_arch = "wasm32"))]
+mod host {
+    use extism_pdk::LogLevel;
+
+    pub fn log(level: LogLevel, line: String) {
+        let enabled = unsafe { extism_pdk::extism::get_log_level() };
+        if level.to_int() < enabled || enabled == i32::MAX {
+            return;
+        }
+        if let Ok(memory) = extism_pdk::Memory::from_bytes(line) {
+            memory.log(level);
+        }
+    }
+}
+
+// Every level is recorded, whatever the level configured on the host
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+mod host {
+    use crate::testing::host_call;
+    use extism_pdk::LogLevel;
+
+    pub fn log(level: LogLevel, line: String) {
+        let _ = host_call("log", |host| {
+            host.logs.push((level, line));
+            Ok(())
+        });
+    }
+}
+
+/// A logger carrying structured fields added to every message.
+///
+/// ```ignore
+/// let logger = Logger::new().with_field("symbol", "BTCUSDT");
+/// logger.info("signal triggered");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Logger {
+    fields: Vec<(String, String)>,
+}
+
+impl Logger {
+    pub fn new() -> Self {
+        Logger::default()
+    }
+
+    /// Adds a field to every message of this logger.
+    pub fn with_field(mut self, key: &str, value: impl Display) -> Self {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Forwards a message to the host if `level` is enabled for this execution.
+    pub fn log(&self, level: LogLevel, message: &str) {
+        host::log(level, self.format(message, Vec::new()))
+    }
+
+    pub fn trace(&self, message: &str) {
+        self.log(LogLevel::Trace, message)
+    }
+
+    pub fn debug(&self, message: &str) {
+        self.log(LogLevel::Debug, message)
+    }
+
+    pub fn info(&self, message: &str) {
+        self.log(LogLevel::Info, message)
+    }
+
+    pub fn warn(&self, message: &str) {
+        self.log(LogLevel::Warn, message)
+    }
+
+    pub fn error(&self, message: &str) {
+        self.log(LogLevel::Error, message)
+    }
+
+    fn format(&self, message: &str, fields: Vec<(String, String)>) -> String {
+        let mut line = message.to_string();
+        for (key, value) in self.fields.iter().chain(&fields) {
+            line.push(' ');
+            line.push_str(key);
+            line.push('=');
+            if value.is_empty() || value.contains([' ', '"', '=']) {
+                line.push_str(&format!("{:?}", value));
+            } else {
+                line.push_str(value);
+            }
+        }
+        line
+    }
+}
+
+impl log::Log for Logger {
+    // Levels are filtered by the host
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let mut fields = Fields(Vec::new());
+        let _ = record.key_values().visit(&mut fields);
+        let line = self.format(&record.args().to_string(), fields.0);
+        host::log(log_level(record.level()), line)
+    }
+
+    fn flush(&self) {}
+}
+
+struct Fields(Vec<(String, String)>);
+
+impl<'kvs> VisitSource<'kvs> for Fields {
+    fn visit_pair(&mut self, key: kv::Key<'kvs>, value: kv::Value<'kvs>) -> Result<(), kv::Error> {
+        self.0.push((key.to_string(), value.to_string()));
+        Ok(())
+    }
+}
+
+fn log_level(level: log::Level) -> LogLevel {
+    match level {
+        log::Level::Error => LogLevel::Error,
+        log::Level::Warn => LogLevel::Warn,
+        log::Level::Info => LogLevel::Info,
+        log::Level::Debug => LogLevel::Debug,
+        log::Level::Trace => LogLevel::Trace,
+    }
+}
+
+static LOGGER: Logger = Logger { fields: Vec::new() };
+
+/// Installs a [`Logger`] without fields as the logger of the [`log`] facade, so
+/// `log::info!` and the other macros reach the execution logs.
+///
+/// Fails if a logger is already installed, e.g. when called twice.
+pub fn init() -> Result<(), log::SetLoggerError> {
+    log::set_logger(&LOGGER)?;
+    log::set_max_level(log::LevelFilter::Trace);
+    Ok(())
+}
+
+/// Logs a message without fields at the info level.
+pub fn info(message: &str) {
+    Logger::new().info(message)
+}
+
+/// Logs a message without fields at the warn level.
+pub fn warn(message: &str) {
+    Logger::new().warn(message)
+}
+
+/// Logs a message without fields at the error level.
+pub fn error(message: &str) {
+    Logger::new().error(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_fields() {
+        let logger = Logger::new()
+            .with_field("symbol", "BTCUSDT")
+            .with_field("price", 50_000.5)
+            .with_field("reason", "cross above");
+        assert_eq!(
+            logger.format("signal", Vec::new()),
+            r#"signal symbol=BTCUSDT price=50000.5 reason="cross above""#
+        );
+        assert_eq!(Logger::new().format("plain", Vec::new()), "plain");
+    }
+
+    #[test]
+    fn test_record_fields() {
+        let pairs: &[(&str, f64)] = &[("price", 50_000.5), ("size", 0.25)];
+        let record = log::Record::builder()
+            .args(format_args!("filled"))
+            .level(log::Level::Warn)
+            .key_values(&pairs)
+            .build();
+        let mut fields = Fields(Vec::new());
+        record.key_values().visit(&mut fields).unwrap();
+        let logger = Logger::new().with_field("symbol", "BTCUSDT");
+        assert_eq!(
+            logger.format(&record.args().to_string(), fields.0),
+            "filled symbol=BTCUSDT price=50000.5 size=0.25"
+        );
+        assert_eq!(log_level(record.level()), LogLevel::Warn);
+    }
+}
@@ -0,0 +1,88 @@
+use rust_decimal::prelude::*;
+use serde::Deserialize;
+
+/// Trading rules of a market, as published by the exchange.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MarketInfo {
+    /// Smallest price increment.
+    pub tick_size: Decimal,
+    /// Smallest quantity increment.
+    pub lot_step: Decimal,
+    /// Smallest accepted order quantity.
+    #[serde(default)]
+    pub min_quantity: Decimal,
+    /// Smallest accepted order value (price times quantity).
+    #[serde(default)]
+    pub min_notional: Decimal,
+    /// Highest leverage allowed on the market, `None` for spot markets.
+    #[serde(default)]
+    pub max_leverage: Option<Decimal>,
+}
+
+impl MarketInfo {
+    /// Rounds a price to the nearest tick.
+    pub fn round_price(&self, price: Decimal) -> Decimal {
+        round_to_step(
+            price,
+            self.tick_size,
+            RoundingStrategy::MidpointAwayFromZero,
+        )
+    }
+
+    /// Rounds a quantity down to the lot step, so an order never exceeds the intended size.
+    pub fn round_quantity(&self, quantity: Decimal) -> Decimal {
+        round_to_step(quantity, self.lot_step, RoundingStrategy::ToZero)
+    }
+
+    /// Converts a raw quantity to an order quantity, `Decimal::ZERO` if it is below the
+    /// market minimum once rounded.
+    pub fn quantity_from_f64(&self, quantity: f64) -> Decimal {
+        let quantity = self.round_quantity(Decimal::from_f64(quantity).unwrap_or(Decimal::ZERO));
+        if quantity < self.min_quantity {
+            Decimal::ZERO
+        } else {
+            quantity
+        }
+    }
+}
+
+fn round_to_step(value: Decimal, step: Decimal, strategy: RoundingStrategy) -> Decimal {
+    if step <= Decimal::ZERO {
+        return value;
+    }
+    ((value / step).round_dp_with_strategy(0, strategy) * step).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn market() -> MarketInfo {
+        serde_json::from_str(
+            r#"{"tick_size": "0.05", "lot_step": 0.001, "min_quantity": "0.01", "min_notional": 10}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_deserialize_market_info() {
+        let market = market();
+        assert_eq!(market.lot_step, dec("0.001"));
+        assert_eq!(market.min_notional, dec("10"));
+        assert_eq!(market.max_leverage, None);
+    }
+
+    #[test]
+    fn test_rounding() {
+        let market = market();
+        assert_eq!(market.round_price(dec("100.07")), dec("100.05"));
+        assert_eq!(market.round_price(dec("100.08")), dec("100.1"));
+        assert_eq!(market.round_quantity(dec("1.2349")), dec("1.234"));
+        assert_eq!(market.quantity_from_f64(0.0099), Decimal::ZERO);
+        assert_eq!(market.quantity_from_f64(0.5), dec("0.5"));
+    }
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Deserializer};
+
+/// A news item about a ticker, with the sentiment scored by the data provider.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NewsItem {
+    /// Publication time in milliseconds since the Unix epoch.
+    pub timestamp: i64,
+    #[serde(default)]
+    pub headline: String,
+    /// Sentiment from `-1.0` (very negative) to `1.0` (very positive).
+    pub score: f64,
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// Average sentiment score of the items published in `[start, end)`, `None` without items.
+pub fn average_sentiment(news: &[NewsItem], start: i64, end: i64) -> Option<f64> {
+    let (sum, count) = news
+        .iter()
+        .filter(|item| (start..end).contains(&item.timestamp))
+        .fold((0.0, 0usize), |(sum, count), item| {
+            (sum + item.score, count + 1)
+        });
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Average sentiment of the items published during the `window` milliseconds ending at
+/// each timestamp (included), e.g. the candle timestamps, so it can be lined up with
+/// prices. `None` where no item was published.
+///
+/// `news` must be sorted by timestamp, like [`TickersData::news`](crate::TickersData).
+pub fn rolling_sentiment(news: &[NewsItem], timestamps: &[i64], window: i64) -> Vec<Option<f64>> {
+    let mut start = 0;
+    let mut end = 0;
+    let mut sum = 0.0;
+    timestamps
+        .iter()
+        .map(|&timestamp| {
+            while end < news.len() && news[end].timestamp <= timestamp {
+                sum += news[end].score;
+                end += 1;
+            }
+            while start < end && news[start].timestamp <= timestamp - window {
+                sum -= news[start].score;
+                start += 1;
+            }
+            (end > start).then(|| sum / (end - start) as f64)
+        })
+        .collect()
+}
+
+// News sorted by timestamp, as expected by the rolling helpers
+pub(crate) fn deserialize_sorted<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<NewsItem>, D::Error> {
+    let mut news = Vec::<NewsItem>::deserialize(deserializer)?;
+    news.sort_by_key(|item| item.timestamp);
+    Ok(news)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn news() -> Vec<NewsItem> {
+        serde_json::from_str(
+            r#"[
+                {"timestamp": 1000, "headline": "Upgrade", "score": 0.8, "source": "wire"},
+                {"timestamp": 2000, "score": -0.4},
+                {"timestamp": 3500, "score": 0.2}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_average_sentiment() {
+        let news = news();
+        assert_eq!(news[0].source.as_deref(), Some("wire"));
+        assert!((average_sentiment(&news, 0, 3000).unwrap() - 0.2).abs() < 1e-12);
+        assert_eq!(average_sentiment(&news, 4000, 5000), None);
+    }
+
+    #[test]
+    fn test_rolling_sentiment() {
+        let values = rolling_sentiment(&news(), &[500, 1000, 2000, 3000, 4000, 6000], 1500);
+        let rounded: Vec<Option<f64>> = values
+            .iter()
+            .map(|v| v.map(|v| (v * 1e9).round() / 1e9))
+            .collect();
+        assert_eq!(
+            rounded,
+            vec![None, Some(0.8), Some(0.2), Some(-0.4), Some(0.2), None]
+        );
+    }
+}
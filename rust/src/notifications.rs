@@ -1,14 +1,45 @@
-use extism_pdk::{WithReturnCode, host_fn};
+use extism_pdk::WithReturnCode;
 
-#[host_fn]
-extern "ExtismHost" {
-    fn add_notification(notification_type: String, notification_target: String, body: String);
+#[cfg(any(not(feature = "test-utils"), target_arch = "wasm32"))]
+mod host {
+    use extism_pdk::host_fn;
+
+    #[host_fn]
+    extern "ExtismHost" {
+        pub fn add_notification(
+            notification_type: String,
+            notification_target: String,
+            body: String,
+        );
+    }
+}
+
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+mod host {
+    use crate::testing::{Notification, host_call};
+    use extism_pdk::Error;
+
+    pub unsafe fn add_notification(
+        notification_type: String,
+        notification_target: String,
+        body: String,
+    ) -> Result<(), Error> {
+        host_call("add_notification", |host| {
+            host.notifications.push(Notification {
+                kind: notification_type,
+                target: notification_target,
+                body,
+            });
+            Ok(())
+        })
+    }
 }
 
 
+#[allow(clippy::io_other_error)]
 pub fn schedule_webhook(path: &str, body: &str) -> Result<(), WithReturnCode<extism_pdk::Error>> {
     unsafe {
-        let res = add_notification("webhook".into(), path.into(), body.into());
+        let res = host::add_notification("webhook".into(), path.into(), body.into());
         if res.is_err() {
             return Err(WithReturnCode::new(
                 extism_pdk::Error::new(std::io::Error::new(
@@ -23,9 +54,10 @@ pub fn schedule_webhook(path: &str, body: &str) -> Result<(), WithReturnCode<ext
 }
 
 
+#[allow(clippy::io_other_error)]
 pub fn schedule_email(email: &str, body: &str) -> Result<(), WithReturnCode<extism_pdk::Error>> {
     unsafe {
-        let res = add_notification("email".into(), email.into(), body.into());
+        let res = host::add_notification("email".into(), email.into(), body.into());
         if res.is_err() {
             return Err(WithReturnCode::new(
                 extism_pdk::Error::new(std::io::Error::new(
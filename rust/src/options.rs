@@ -0,0 +1,196 @@
+use serde::Deserialize;
+
+/// Right given by an option contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+/// Sensitivities of an option price, as computed by the data provider.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Greeks {
+    pub delta: f64,
+    pub gamma: f64,
+    /// Price change per day.
+    pub theta: f64,
+    /// Price change per volatility point.
+    pub vega: f64,
+    pub rho: f64,
+}
+
+/// A listed option contract with its latest quote.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OptionContract {
+    pub symbol: String,
+    pub kind: OptionKind,
+    pub strike: f64,
+    /// Expiration time in milliseconds since the Unix epoch.
+    pub expiry: i64,
+    #[serde(default)]
+    pub bid: Option<f64>,
+    #[serde(default)]
+    pub ask: Option<f64>,
+    #[serde(default)]
+    pub last: Option<f64>,
+    #[serde(default)]
+    pub volume: f64,
+    #[serde(default)]
+    pub open_interest: f64,
+    /// Implied volatility as a fraction, e.g. `0.35` for 35%.
+    #[serde(default)]
+    pub implied_volatility: Option<f64>,
+    #[serde(default)]
+    pub greeks: Option<Greeks>,
+}
+
+impl OptionContract {
+    /// Midpoint of the bid and ask, falling back to the last trade price.
+    pub fn mid(&self) -> Option<f64> {
+        match (self.bid, self.ask) {
+            (Some(bid), Some(ask)) => Some((bid + ask) / 2.0),
+            _ => self.last,
+        }
+    }
+}
+
+/// Option contracts listed on an underlying, when provided by the host.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OptionsChain {
+    /// Price of the underlying when the chain was captured.
+    #[serde(default)]
+    pub underlying_price: Option<f64>,
+    pub contracts: Vec<OptionContract>,
+}
+
+impl OptionsChain {
+    pub fn calls(&self) -> impl Iterator<Item = &OptionContract> {
+        self.of_kind(OptionKind::Call)
+    }
+
+    pub fn puts(&self) -> impl Iterator<Item = &OptionContract> {
+        self.of_kind(OptionKind::Put)
+    }
+
+    fn of_kind(&self, kind: OptionKind) -> impl Iterator<Item = &OptionContract> {
+        self.contracts.iter().filter(move |c| c.kind == kind)
+    }
+
+    /// Distinct expiries of the chain, sorted.
+    pub fn expiries(&self) -> Vec<i64> {
+        let mut expiries: Vec<i64> = self.contracts.iter().map(|c| c.expiry).collect();
+        expiries.sort_unstable();
+        expiries.dedup();
+        expiries
+    }
+
+    /// First expiry at or after `timestamp`.
+    pub fn nearest_expiry(&self, timestamp: i64) -> Option<i64> {
+        self.contracts
+            .iter()
+            .map(|c| c.expiry)
+            .filter(|&expiry| expiry >= timestamp)
+            .min()
+    }
+
+    /// Contracts expiring at `expiry`, sorted by strike then calls first.
+    pub fn expiring(&self, expiry: i64) -> Vec<&OptionContract> {
+        let mut contracts: Vec<_> = self
+            .contracts
+            .iter()
+            .filter(|c| c.expiry == expiry)
+            .collect();
+        contracts.sort_by(|a, b| {
+            a.strike
+                .total_cmp(&b.strike)
+                .then((a.kind == OptionKind::Put).cmp(&(b.kind == OptionKind::Put)))
+        });
+        contracts
+    }
+
+    /// Strike closest to the underlying price, `None` without a price or contracts.
+    pub fn atm_strike(&self) -> Option<f64> {
+        let price = self.underlying_price?;
+        self.contracts
+            .iter()
+            .map(|c| c.strike)
+            .min_by(|a, b| (a - price).abs().total_cmp(&(b - price).abs()))
+    }
+
+    /// Put volume divided by call volume, `None` without call volume.
+    pub fn put_call_ratio(&self) -> Option<f64> {
+        ratio(
+            self.puts().map(|c| c.volume),
+            self.calls().map(|c| c.volume),
+        )
+    }
+
+    /// Put open interest divided by call open interest, `None` without call open interest.
+    pub fn put_call_open_interest_ratio(&self) -> Option<f64> {
+        ratio(
+            self.puts().map(|c| c.open_interest),
+            self.calls().map(|c| c.open_interest),
+        )
+    }
+}
+
+fn ratio(puts: impl Iterator<Item = f64>, calls: impl Iterator<Item = f64>) -> Option<f64> {
+    let calls: f64 = calls.sum();
+    (calls > 0.0).then(|| puts.sum::<f64>() / calls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> OptionsChain {
+        serde_json::from_str(
+            r#"{
+                "underlying_price": 102.0,
+                "contracts": [
+                    {"symbol": "C100", "kind": "call", "strike": 100, "expiry": 2000, "bid": 3, "ask": 4, "volume": 50, "open_interest": 400,
+                     "implied_volatility": 0.4, "greeks": {"delta": 0.6, "gamma": 0.05}},
+                    {"symbol": "P100", "kind": "put", "strike": 100, "expiry": 2000, "last": 1.5, "volume": 25, "open_interest": 100},
+                    {"symbol": "C110", "kind": "call", "strike": 110, "expiry": 1000, "volume": 50},
+                    {"symbol": "P95", "kind": "put", "strike": 95, "expiry": 1000, "volume": 75}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_deserialize_chain() {
+        let chain = chain();
+        let call = &chain.contracts[0];
+        assert_eq!(call.kind, OptionKind::Call);
+        assert_eq!(call.mid(), Some(3.5));
+        assert_eq!(call.greeks.unwrap().gamma, 0.05);
+        assert_eq!(call.greeks.unwrap().theta, 0.0);
+        assert_eq!(chain.contracts[1].mid(), Some(1.5));
+    }
+
+    #[test]
+    fn test_expiry_filtering() {
+        let chain = chain();
+        assert_eq!(chain.expiries(), vec![1000, 2000]);
+        assert_eq!(chain.nearest_expiry(1500), Some(2000));
+        assert_eq!(chain.nearest_expiry(3000), None);
+        let symbols: Vec<&str> = chain
+            .expiring(2000)
+            .iter()
+            .map(|c| c.symbol.as_str())
+            .collect();
+        assert_eq!(symbols, vec!["C100", "P100"]);
+        assert_eq!(chain.atm_strike(), Some(100.0));
+    }
+
+    #[test]
+    fn test_put_call_ratios() {
+        let chain = chain();
+        assert_eq!(chain.put_call_ratio(), Some(1.0));
+        assert_eq!(chain.put_call_open_interest_ratio(), Some(0.25));
+    }
+}
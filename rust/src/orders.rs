@@ -0,0 +1,609 @@
+//! Order model shared by order outputs and the order execution host calls.
+use std::fmt;
+
+use extism_pdk::{Error, WithReturnCode};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::MarketInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    pub fn opposite(self) -> Self {
+        match self {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        }
+    }
+}
+
+/// Distance between the market price and the stop of a trailing-stop order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrailingDistance {
+    /// Absolute price distance.
+    Amount(Decimal),
+    /// Distance in percent of the price, e.g. `1.5` for 1.5%.
+    Percent(Decimal),
+}
+
+/// How and when an order executes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OrderType {
+    /// Executes immediately at the best available price.
+    Market,
+    /// Executes at `price` or better.
+    Limit { price: Decimal },
+    /// Becomes a market order once the price reaches `stop_price`.
+    Stop { stop_price: Decimal },
+    /// Becomes a limit order at `limit_price` once the price reaches `stop_price`.
+    StopLimit {
+        stop_price: Decimal,
+        limit_price: Decimal,
+    },
+    /// Stop order whose stop follows the price at a fixed distance.
+    TrailingStop { distance: TrailingDistance },
+    /// One-cancels-the-other: a limit order at `price` paired with a stop at `stop_price`
+    /// (a stop-limit if `stop_limit_price` is set). Filling one side cancels the other.
+    Oco {
+        price: Decimal,
+        stop_price: Decimal,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stop_limit_price: Option<Decimal>,
+    },
+}
+
+/// How long an order stays active.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeInForce {
+    /// Good till canceled.
+    #[default]
+    Gtc,
+    /// Immediate or cancel: fills what it can immediately and cancels the rest.
+    Ioc,
+    /// Fill or kill: fills entirely immediately or is canceled.
+    Fok,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// A complete order description.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderRequest {
+    pub symbol: String,
+    pub exchange: String,
+    pub side: OrderSide,
+    #[serde(flatten)]
+    pub order_type: OrderType,
+    pub quantity: Decimal,
+    #[serde(default)]
+    pub time_in_force: TimeInForce,
+    /// Only reduces an existing position, never opens or increases one.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub reduce_only: bool,
+    /// Only adds liquidity: rejected instead of executing immediately as a taker.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub post_only: bool,
+    /// Leverage of the position opened by the order, `None` for spot orders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leverage: Option<Decimal>,
+    /// Identifier chosen by the function, echoed back by the exchange.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_order_id: Option<String>,
+}
+
+impl OrderRequest {
+    pub fn new(
+        symbol: &str,
+        exchange: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+    ) -> Self {
+        OrderRequest {
+            symbol: symbol.to_string(),
+            exchange: exchange.to_string(),
+            side,
+            order_type,
+            quantity,
+            time_in_force: TimeInForce::default(),
+            reduce_only: false,
+            post_only: false,
+            leverage: None,
+            client_order_id: None,
+        }
+    }
+
+    pub fn market(symbol: &str, exchange: &str, side: OrderSide, quantity: Decimal) -> Self {
+        OrderRequest::new(symbol, exchange, side, OrderType::Market, quantity)
+    }
+
+    pub fn limit(
+        symbol: &str,
+        exchange: &str,
+        side: OrderSide,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> Self {
+        OrderRequest::new(symbol, exchange, side, OrderType::Limit { price }, quantity)
+    }
+
+    pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
+
+    pub fn with_reduce_only(mut self) -> Self {
+        self.reduce_only = true;
+        self
+    }
+
+    pub fn with_post_only(mut self) -> Self {
+        self.post_only = true;
+        self
+    }
+
+    pub fn with_client_order_id(mut self, client_order_id: &str) -> Self {
+        self.client_order_id = Some(client_order_id.to_string());
+        self
+    }
+
+    pub fn with_leverage(mut self, leverage: Decimal) -> Self {
+        self.leverage = Some(leverage);
+        self
+    }
+
+    /// Prices set on the order, which must all be multiples of the tick size.
+    fn prices(&self) -> Vec<Decimal> {
+        match self.order_type {
+            OrderType::Market => vec![],
+            OrderType::Limit { price } => vec![price],
+            OrderType::Stop { stop_price } => vec![stop_price],
+            OrderType::StopLimit {
+                stop_price,
+                limit_price,
+            } => vec![stop_price, limit_price],
+            OrderType::TrailingStop { distance } => match distance {
+                TrailingDistance::Amount(amount) => vec![amount],
+                TrailingDistance::Percent(_) => vec![],
+            },
+            OrderType::Oco {
+                price,
+                stop_price,
+                stop_limit_price,
+            } => [Some(price), Some(stop_price), stop_limit_price]
+                .into_iter()
+                .flatten()
+                .collect(),
+        }
+    }
+
+    /// Checks the order against the market rules before submission.
+    ///
+    /// The notional is only checked for orders with a limit or stop price, since the
+    /// execution price of market and trailing orders is not known in advance.
+    pub fn validate(&self, market: &MarketInfo) -> Result<(), Vec<OrderViolation>> {
+        let mut violations = Vec::new();
+        if self.quantity <= Decimal::ZERO {
+            violations.push(OrderViolation::NonPositiveQuantity {
+                quantity: self.quantity,
+            });
+        } else if self.quantity < market.min_quantity {
+            violations.push(OrderViolation::BelowMinQuantity {
+                quantity: self.quantity,
+                min_quantity: market.min_quantity,
+            });
+        }
+        if !is_multiple(self.quantity, market.lot_step) {
+            violations.push(OrderViolation::InvalidLotStep {
+                quantity: self.quantity,
+                lot_step: market.lot_step,
+            });
+        }
+        let prices = self.prices();
+        for &price in &prices {
+            if !is_multiple(price, market.tick_size) {
+                violations.push(OrderViolation::InvalidTickSize {
+                    price,
+                    tick_size: market.tick_size,
+                });
+            }
+        }
+        if let Some(&price) = prices.first() {
+            let notional = price * self.quantity;
+            let is_trailing = matches!(self.order_type, OrderType::TrailingStop { .. });
+            if !is_trailing && self.quantity > Decimal::ZERO && notional < market.min_notional {
+                violations.push(OrderViolation::BelowMinNotional {
+                    notional,
+                    min_notional: market.min_notional,
+                });
+            }
+        }
+        if let Some(leverage) = self.leverage {
+            let max_leverage = market.max_leverage.unwrap_or(Decimal::ONE);
+            if leverage > max_leverage {
+                violations.push(OrderViolation::LeverageTooHigh {
+                    leverage,
+                    max_leverage,
+                });
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Like [`validate`](Self::validate), but reports the violations as an error with
+    /// return code 15, ready to be returned from the plugin function.
+    pub fn check(&self, market: &MarketInfo) -> Result<(), WithReturnCode<Error>> {
+        self.validate(market).map_err(|violations| {
+            let messages: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+            WithReturnCode::new(
+                Error::new(std::io::Error::other(format!(
+                    "Invalid order on {}: {}",
+                    self.symbol,
+                    messages.join(", ")
+                ))),
+                15,
+            )
+        })
+    }
+}
+
+fn is_multiple(value: Decimal, step: Decimal) -> bool {
+    step <= Decimal::ZERO || (value % step).is_zero()
+}
+
+/// A market rule broken by an order, reported by [`OrderRequest::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum OrderViolation {
+    NonPositiveQuantity {
+        quantity: Decimal,
+    },
+    BelowMinQuantity {
+        quantity: Decimal,
+        min_quantity: Decimal,
+    },
+    InvalidLotStep {
+        quantity: Decimal,
+        lot_step: Decimal,
+    },
+    InvalidTickSize {
+        price: Decimal,
+        tick_size: Decimal,
+    },
+    BelowMinNotional {
+        notional: Decimal,
+        min_notional: Decimal,
+    },
+    /// Spot markets, without a maximum leverage, only accept a leverage of 1.
+    LeverageTooHigh {
+        leverage: Decimal,
+        max_leverage: Decimal,
+    },
+}
+
+impl fmt::Display for OrderViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderViolation::NonPositiveQuantity { quantity } => {
+                write!(f, "quantity {} must be positive", quantity)
+            }
+            OrderViolation::BelowMinQuantity {
+                quantity,
+                min_quantity,
+            } => write!(f, "quantity {} below minimum {}", quantity, min_quantity),
+            OrderViolation::InvalidLotStep { quantity, lot_step } => {
+                write!(
+                    f,
+                    "quantity {} not a multiple of lot step {}",
+                    quantity, lot_step
+                )
+            }
+            OrderViolation::InvalidTickSize { price, tick_size } => {
+                write!(
+                    f,
+                    "price {} not a multiple of tick size {}",
+                    price, tick_size
+                )
+            }
+            OrderViolation::BelowMinNotional {
+                notional,
+                min_notional,
+            } => write!(f, "notional {} below minimum {}", notional, min_notional),
+            OrderViolation::LeverageTooHigh {
+                leverage,
+                max_leverage,
+            } => write!(f, "leverage {} above maximum {}", leverage, max_leverage),
+        }
+    }
+}
+
+/// An entry order with its take-profit and stop-loss exits.
+///
+/// The exits are reduce-only orders on the opposite side for the entry quantity. When the
+/// entry has a client order id, the exits are linked to it with the `-tp` and `-sl` suffixes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BracketOrder {
+    pub entry: OrderRequest,
+    pub take_profit: OrderRequest,
+    pub stop_loss: OrderRequest,
+}
+
+impl BracketOrder {
+    /// Builds the exits of `entry` around the expected `entry_price`.
+    ///
+    /// The stop loss sits `risk` away from the entry price and the take profit
+    /// `risk * reward_ratio` away on the other side, both rounded to the market tick size.
+    pub fn new(
+        entry: OrderRequest,
+        entry_price: Decimal,
+        risk: Decimal,
+        reward_ratio: Decimal,
+        market: &MarketInfo,
+    ) -> Self {
+        let reward = risk * reward_ratio;
+        let (stop_price, target_price) = match entry.side {
+            OrderSide::Buy => (entry_price - risk, entry_price + reward),
+            OrderSide::Sell => (entry_price + risk, entry_price - reward),
+        };
+        let exit = |order_type: OrderType, suffix: &str| OrderRequest {
+            client_order_id: entry
+                .client_order_id
+                .as_ref()
+                .map(|id| format!("{}-{}", id, suffix)),
+            ..OrderRequest::new(
+                &entry.symbol,
+                &entry.exchange,
+                entry.side.opposite(),
+                order_type,
+                entry.quantity,
+            )
+            .with_reduce_only()
+        };
+        BracketOrder {
+            take_profit: exit(
+                OrderType::Limit {
+                    price: market.round_price(target_price),
+                },
+                "tp",
+            ),
+            stop_loss: exit(
+                OrderType::Stop {
+                    stop_price: market.round_price(stop_price),
+                },
+                "sl",
+            ),
+            entry,
+        }
+    }
+
+    /// Both exits as a single OCO order, for exchanges that support them natively.
+    ///
+    /// Fails with return code 15 if the take profit is not a limit order or the stop
+    /// loss not a stop order, which only happens when the exits were changed after
+    /// [`new`](Self::new).
+    pub fn exit_oco(&self) -> Result<OrderRequest, WithReturnCode<Error>> {
+        let (OrderType::Limit { price }, OrderType::Stop { stop_price }) =
+            (self.take_profit.order_type, self.stop_loss.order_type)
+        else {
+            return Err(WithReturnCode::new(
+                Error::new(std::io::Error::other(format!(
+                    "Invalid bracket on {}: exits must be a limit and a stop order",
+                    self.entry.symbol
+                ))),
+                15,
+            ));
+        };
+        Ok(OrderRequest {
+            order_type: OrderType::Oco {
+                price,
+                stop_price,
+                stop_limit_price: None,
+            },
+            client_order_id: self
+                .entry
+                .client_order_id
+                .as_ref()
+                .map(|id| format!("{}-oco", id)),
+            ..self.take_profit.clone()
+        })
+    }
+
+    /// The three orders, in submission order.
+    pub fn orders(&self) -> [&OrderRequest; 3] {
+        [&self.entry, &self.take_profit, &self.stop_loss]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_limit_request_serialization() {
+        let request = OrderRequest::limit(
+            "BTCUSDT",
+            "binance",
+            OrderSide::Buy,
+            Decimal::new(1, 2),
+            Decimal::new(50_000, 0),
+        )
+        .with_post_only();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "symbol": "BTCUSDT",
+                "exchange": "binance",
+                "side": "buy",
+                "type": "limit",
+                "price": "50000",
+                "quantity": "0.01",
+                "time_in_force": "gtc",
+                "post_only": true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_order_types_round_trip() {
+        let types = [
+            OrderType::Market,
+            OrderType::Stop {
+                stop_price: Decimal::new(95, 0),
+            },
+            OrderType::StopLimit {
+                stop_price: Decimal::new(95, 0),
+                limit_price: Decimal::new(94, 0),
+            },
+            OrderType::TrailingStop {
+                distance: TrailingDistance::Percent(Decimal::new(15, 1)),
+            },
+            OrderType::Oco {
+                price: Decimal::new(110, 0),
+                stop_price: Decimal::new(95, 0),
+                stop_limit_price: None,
+            },
+        ];
+        for order_type in types {
+            let request = OrderRequest::new("X", "y", OrderSide::Sell, order_type, Decimal::ONE)
+                .with_reduce_only()
+                .with_time_in_force(TimeInForce::Ioc);
+            let json = serde_json::to_string(&request).unwrap();
+            assert_eq!(
+                serde_json::from_str::<OrderRequest>(&json).unwrap(),
+                request
+            );
+        }
+    }
+
+    fn market() -> MarketInfo {
+        MarketInfo {
+            tick_size: Decimal::new(5, 1),
+            lot_step: Decimal::new(1, 3),
+            min_quantity: Decimal::ZERO,
+            min_notional: Decimal::ZERO,
+            max_leverage: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_valid_order() {
+        let order = OrderRequest::limit(
+            "BTCUSDT",
+            "binance",
+            OrderSide::Buy,
+            Decimal::new(15, 3),
+            Decimal::new(1005, 1),
+        );
+        assert_eq!(order.validate(&market()), Ok(()));
+        assert!(order.check(&market()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_violations() {
+        let market = MarketInfo {
+            min_quantity: Decimal::new(1, 2),
+            min_notional: Decimal::new(10, 0),
+            max_leverage: Some(Decimal::new(5, 0)),
+            ..market()
+        };
+        let order = OrderRequest::limit(
+            "BTCUSDT",
+            "binance",
+            OrderSide::Buy,
+            Decimal::new(15, 4),
+            Decimal::new(1003, 1),
+        )
+        .with_leverage(Decimal::new(10, 0));
+        let violations = order.validate(&market).unwrap_err();
+        assert_eq!(violations.len(), 5);
+        assert!(violations.contains(&OrderViolation::InvalidTickSize {
+            price: Decimal::new(1003, 1),
+            tick_size: Decimal::new(5, 1),
+        }));
+        assert!(violations.contains(&OrderViolation::LeverageTooHigh {
+            leverage: Decimal::new(10, 0),
+            max_leverage: Decimal::new(5, 0),
+        }));
+        let err = order.check(&market).unwrap_err();
+        assert_eq!(err.1, 15);
+        assert!(err.0.to_string().contains("below minimum 0.01"));
+    }
+
+    #[test]
+    fn test_validate_market_order_skips_notional() {
+        let market = MarketInfo {
+            min_notional: Decimal::new(10, 0),
+            ..market()
+        };
+        let order = OrderRequest::market("X", "y", OrderSide::Sell, Decimal::new(1, 3));
+        assert_eq!(order.validate(&market), Ok(()));
+        let order = order.with_leverage(Decimal::new(2, 0));
+        assert_eq!(order.validate(&market).unwrap_err().len(), 1);
+    }
+
+    #[test]
+    fn test_bracket_order() {
+        let market = market();
+        let entry = OrderRequest::market("BTCUSDT", "binance", OrderSide::Buy, Decimal::ONE)
+            .with_client_order_id("b1");
+        let bracket = BracketOrder::new(
+            entry,
+            Decimal::new(1000, 0),
+            Decimal::new(333, 1),
+            Decimal::new(2, 0),
+            &market,
+        );
+        assert_eq!(
+            bracket.take_profit.order_type,
+            OrderType::Limit {
+                price: Decimal::new(10665, 1)
+            }
+        );
+        assert_eq!(
+            bracket.stop_loss.order_type,
+            OrderType::Stop {
+                stop_price: Decimal::new(9665, 1)
+            }
+        );
+        assert_eq!(bracket.stop_loss.side, OrderSide::Sell);
+        assert!(bracket.take_profit.reduce_only);
+        assert_eq!(bracket.stop_loss.client_order_id.as_deref(), Some("b1-sl"));
+        let oco = bracket.exit_oco().unwrap();
+        assert_eq!(oco.client_order_id.as_deref(), Some("b1-oco"));
+        assert!(matches!(oco.order_type, OrderType::Oco { .. }));
+
+        let swapped = BracketOrder {
+            take_profit: bracket.stop_loss.clone(),
+            stop_loss: bracket.take_profit.clone(),
+            ..bracket
+        };
+        assert_eq!(swapped.exit_oco().unwrap_err().1, 15);
+    }
+
+    #[test]
+    fn test_trailing_stop_serialization() {
+        let order_type = OrderType::TrailingStop {
+            distance: TrailingDistance::Amount(Decimal::new(50, 0)),
+        };
+        assert_eq!(
+            serde_json::to_value(order_type).unwrap(),
+            json!({"type": "trailing_stop", "distance": {"amount": "50"}})
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use extism_pdk::{Error, ToBytes, WithReturnCode};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Structured output returned by a function to the host, encoded as JSON.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct FunctionOutput(Value);
+
+impl FunctionOutput {
+    /// Builds an output from any serializable value.
+    pub fn new<T: Serialize>(data: &T) -> Result<Self, WithReturnCode<Error>> {
+        serde_json::to_value(data).map(FunctionOutput).map_err(|e| {
+            WithReturnCode::new(
+                Error::new(std::io::Error::other(format!(
+                    "Failed to serialize output: {}",
+                    e
+                ))),
+                9,
+            )
+        })
+    }
+
+    pub fn as_value(&self) -> &Value {
+        &self.0
+    }
+
+    pub fn into_value(self) -> Value {
+        self.0
+    }
+}
+
+impl ToBytes<'_> for FunctionOutput {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self) -> Result<Self::Bytes, Error> {
+        Ok(serde_json::to_vec(&self.0)?)
+    }
+}
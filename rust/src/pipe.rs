@@ -0,0 +1,131 @@
+//! Typed data passed between chained functions.
+//!
+//! A producer emits its output tagged with a content type, so the consumer can check that
+//! it reads the data it expects instead of silently misparsing the output of a function
+//! that changed. Implement [`PipeOutput`] with `#[derive(PipeOutput)]` on a type shared by
+//! both functions.
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{AbiError, FunctionOutput};
+
+/// A type that can be emitted as piped data and read back on the consuming side.
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize, PipeOutput)]
+/// #[pipe(content_type = "signals/v1")]
+/// struct Signals {
+///     entries: Vec<(i64, f64)>,
+/// }
+/// ```
+///
+/// Without a `pipe` attribute, the content type is the name of the type.
+pub trait PipeOutput: Serialize + DeserializeOwned {
+    /// Identifies the type in the piped data.
+    const CONTENT_TYPE: &'static str;
+
+    /// Builds the output of the producing function, tagged with the content type.
+    fn to_output(&self) -> Result<FunctionOutput, AbiError> {
+        FunctionOutput::new(&Envelope {
+            content_type: Self::CONTENT_TYPE,
+            data: self,
+        })
+    }
+
+    /// Reads piped data produced with [`to_output`](Self::to_output). Untagged data, from
+    /// a producer not using this trait, is parsed as is.
+    ///
+    /// Fails with return code 27 if the data has another content type or does not parse.
+    fn from_pipe(data: &str) -> Result<Self, AbiError> {
+        let value: Value = serde_json::from_str(data).map_err(|e| pipe_error(e.to_string()))?;
+        let value = match serde_json::from_value::<Envelope<String, Value>>(value.clone()) {
+            Ok(envelope) if envelope.content_type == Self::CONTENT_TYPE => envelope.data,
+            Ok(envelope) => {
+                return Err(pipe_error(format!(
+                    "expected {}, got {}",
+                    Self::CONTENT_TYPE,
+                    envelope.content_type
+                )));
+            }
+            Err(_) => value,
+        };
+        serde_json::from_value(value).map_err(|e| pipe_error(e.to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<C, T> {
+    content_type: C,
+    data: T,
+}
+
+fn pipe_error(detail: String) -> AbiError {
+    AbiError::new(
+        extism_pdk::Error::new(std::io::Error::other(format!(
+            "Failed to read piped data: {}",
+            detail
+        ))),
+        27,
+    )
+}
+
+#[cfg(all(test, feature = "macros"))]
+mod tests {
+    use super::*;
+    use crate::PipeOutput;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, PipeOutput)]
+    #[pipe(content_type = "signals/v1")]
+    struct Signals {
+        entries: Vec<(i64, f64)>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, PipeOutput)]
+    struct Levels {
+        support: f64,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let signals = Signals {
+            entries: vec![(1, 0.5)],
+        };
+        let data = serde_json::to_string(&signals.to_output().unwrap()).unwrap();
+        assert_eq!(
+            data,
+            r#"{"content_type":"signals/v1","data":{"entries":[[1,0.5]]}}"#
+        );
+        assert_eq!(Signals::from_pipe(&data).unwrap(), signals);
+        assert_eq!(Levels::CONTENT_TYPE, "Levels");
+        assert_eq!(Levels::from_pipe(&data).unwrap_err().1, 27);
+    }
+
+    #[test]
+    fn test_untagged_data() {
+        assert_eq!(
+            Levels::from_pipe(r#"{"support": 1.5}"#).unwrap(),
+            Levels { support: 1.5 }
+        );
+        assert_eq!(Levels::from_pipe("[]").unwrap_err().1, 27);
+    }
+
+    #[test]
+    fn test_typed_pipe_accessor() {
+        let data = serde_json::to_string(&Levels { support: 2.0 }.to_output().unwrap()).unwrap();
+        let payload = serde_json::json!({
+            "tickers_data": {},
+            "piped_data": {"levels": data},
+            "call_arguments": {},
+        });
+        let args = crate::FunctionArgs::from_json_str(&payload.to_string()).unwrap();
+        assert_eq!(args.get_pipe::<Levels>("levels").unwrap().support, 2.0);
+        assert_eq!(args.get_pipe::<Signals>("levels").unwrap_err().1, 27);
+        assert_eq!(args.get_pipe::<Levels>("missing").unwrap_err().1, 2);
+        assert_eq!(
+            crate::PipePayload::new(data)
+                .parse_pipe::<Levels>()
+                .unwrap(),
+            Levels { support: 2.0 }
+        );
+    }
+}
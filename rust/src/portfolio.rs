@@ -0,0 +1,154 @@
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+/// A position held in the user's book.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Holding {
+    pub symbol: String,
+    #[serde(default)]
+    pub exchange: Option<String>,
+    /// Held quantity, negative for short positions.
+    pub quantity: Decimal,
+    /// Average price paid per unit.
+    pub cost_basis: Decimal,
+}
+
+impl Holding {
+    /// Total amount paid for the holding.
+    pub fn cost(&self) -> Decimal {
+        self.quantity * self.cost_basis
+    }
+
+    pub fn market_value(&self, price: Decimal) -> Decimal {
+        self.quantity * price
+    }
+
+    pub fn unrealized_pnl(&self, price: Decimal) -> Decimal {
+        self.quantity * (price - self.cost_basis)
+    }
+
+    /// Unrealized profit as a fraction of the cost, `None` for a zero cost.
+    pub fn unrealized_return(&self, price: Decimal) -> Option<Decimal> {
+        let cost = self.cost().abs();
+        (!cost.is_zero()).then(|| self.unrealized_pnl(price) / cost)
+    }
+}
+
+/// Snapshot of the user's holdings and cash, when provided by the host.
+///
+/// Helpers valuing the book take the price of each holding from a closure, e.g. the last
+/// close from [`FunctionArgs::latest_close`](crate::FunctionArgs::latest_close), and
+/// return `None` when a price is missing.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Portfolio {
+    #[serde(default)]
+    pub holdings: Vec<Holding>,
+    #[serde(default)]
+    pub cash: Decimal,
+    /// Currency of the cash and of the cost bases.
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+impl Portfolio {
+    pub fn holding(&self, symbol: &str) -> Option<&Holding> {
+        self.holdings.iter().find(|h| h.symbol == symbol)
+    }
+
+    pub fn total_cost(&self) -> Decimal {
+        self.holdings.iter().map(Holding::cost).sum()
+    }
+
+    /// Cash plus the market value of every holding.
+    pub fn equity(&self, price: impl Fn(&Holding) -> Option<Decimal>) -> Option<Decimal> {
+        self.holdings
+            .iter()
+            .map(|h| price(h).map(|p| h.market_value(p)))
+            .sum::<Option<Decimal>>()
+            .map(|value| value + self.cash)
+    }
+
+    pub fn unrealized_pnl(&self, price: impl Fn(&Holding) -> Option<Decimal>) -> Option<Decimal> {
+        self.holdings
+            .iter()
+            .map(|h| price(h).map(|p| h.unrealized_pnl(p)))
+            .sum()
+    }
+
+    /// Market value of every holding as a fraction of the equity, to compare with target
+    /// weights when rebalancing. `None` for a non-positive equity.
+    pub fn weights(
+        &self,
+        price: impl Fn(&Holding) -> Option<Decimal>,
+    ) -> Option<Vec<(&Holding, Decimal)>> {
+        let values: Vec<(&Holding, Decimal)> = self
+            .holdings
+            .iter()
+            .map(|h| price(h).map(|p| (h, h.market_value(p))))
+            .collect::<Option<_>>()?;
+        let equity = self.cash + values.iter().map(|(_, value)| value).sum::<Decimal>();
+        if equity <= Decimal::ZERO {
+            return None;
+        }
+        Some(
+            values
+                .into_iter()
+                .map(|(h, value)| (h, value / equity))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::prelude::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn portfolio() -> Portfolio {
+        serde_json::from_str(
+            r#"{
+                "holdings": [
+                    {"symbol": "BTCUSDT", "exchange": "binance", "quantity": "0.5", "cost_basis": "40000"},
+                    {"symbol": "ETHUSDT", "quantity": "-2", "cost_basis": "3000"}
+                ],
+                "cash": "5000",
+                "currency": "USDT"
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn price(holding: &Holding) -> Option<Decimal> {
+        match holding.symbol.as_str() {
+            "BTCUSDT" => Some(dec("50000")),
+            "ETHUSDT" => Some(dec("2500")),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_holding_pnl() {
+        let portfolio = portfolio();
+        let btc = portfolio.holding("BTCUSDT").unwrap();
+        assert_eq!(btc.unrealized_pnl(dec("50000")), dec("5000"));
+        assert_eq!(btc.unrealized_return(dec("50000")), Some(dec("0.25")));
+        let eth = portfolio.holding("ETHUSDT").unwrap();
+        assert_eq!(eth.unrealized_pnl(dec("2500")), dec("1000"));
+    }
+
+    #[test]
+    fn test_portfolio_valuation() {
+        let portfolio = portfolio();
+        assert_eq!(portfolio.total_cost(), dec("14000"));
+        assert_eq!(portfolio.equity(price), Some(dec("25000")));
+        assert_eq!(portfolio.unrealized_pnl(price), Some(dec("6000")));
+        let weights = portfolio.weights(price).unwrap();
+        assert_eq!(weights[0].1, dec("1"));
+        assert_eq!(weights[1].1, dec("-0.2"));
+        assert_eq!(portfolio.equity(|_| None), None);
+    }
+}
@@ -0,0 +1,29 @@
+//! The items most plugins need, in a single import:
+//!
+//! ```ignore
+//! use exchange_outpost_abi::prelude::*;
+//!
+//! #[exchange_function]
+//! pub fn run(args: FunctionArgs) -> Result<Signal, AbiError> { ... }
+//! ```
+//!
+//! `plugin_fn` and `FnResult` are included for entry points written the extism way, which
+//! still needs `extism-pdk` as a direct dependency of the plugin.
+#[cfg(feature = "macros")]
+pub use crate::exchange_function;
+#[cfg(feature = "indicators")]
+pub use crate::indicators::{Indicator, RollingExt};
+pub use crate::{
+    AbiError, Candle, ExecutionMode, FunctionArgs, FunctionOutput, PipeOutput, PipePayload,
+    ResultExt, Secret, TickersData, Timeframe, call_function, get_secret, now,
+};
+#[cfg(feature = "decimal")]
+pub use crate::{DecimalString, MarketInfo};
+#[cfg(feature = "notifications")]
+pub use crate::{schedule_email, schedule_webhook};
+pub use extism_pdk::{FnResult, Json, WithReturnCode, plugin_fn};
+#[cfg(feature = "decimal")]
+pub use rust_decimal::prelude::{
+    Decimal, FromPrimitive, FromStr, One, RoundingStrategy, Signed, ToPrimitive, Zero,
+};
+pub use serde::{Deserialize, Serialize};
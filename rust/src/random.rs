@@ -0,0 +1,118 @@
+//! Deterministic random numbers for Monte-Carlo style functions.
+//!
+//! The generator is seeded by the host, which records the seed of every run, so a run can
+//! be reproduced exactly. WebAssembly has no portable entropy source, so this is also the
+//! only supported way to get random numbers inside a function.
+use std::cell::Cell;
+
+use extism_pdk::{Error, Json, WithReturnCode};
+use rand::SeedableRng;
+
+pub use rand::{Rng, RngCore};
+
+#[cfg(any(not(feature = "test-utils"), target_arch = "wasm32"))]
+mod host {
+    use extism_pdk::{Json, host_fn};
+
+    #[host_fn]
+    extern "ExtismHost" {
+        pub fn get_random_seed() -> Json<u64>;
+    }
+}
+
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+mod host {
+    use crate::testing::host_call;
+    use extism_pdk::{Error, Json};
+
+    pub unsafe fn get_random_seed() -> Result<Json<u64>, Error> {
+        host_call("get_random_seed", |host| Ok(Json(host.random_seed)))
+    }
+}
+
+/// The generator returned by [`rng`].
+pub type DeterministicRng = rand_chacha::ChaCha8Rng;
+
+thread_local! {
+    static SEED_OVERRIDE: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Forces the seed used by [`rng`] instead of asking the host, e.g. in tests.
+///
+/// The override is local to the calling thread, so parallel tests do not see each
+/// other's seeds.
+pub fn set_seed(seed: u64) {
+    SEED_OVERRIDE.set(Some(seed));
+}
+
+/// Returns to seeding [`rng`] from the host.
+pub fn clear_seed() {
+    SEED_OVERRIDE.set(None);
+}
+
+fn seed_override() -> Option<u64> {
+    SEED_OVERRIDE.get()
+}
+
+/// Returns the seed [`rng`] starts from.
+///
+/// Fails with return code 23 if no override is set and the host cannot provide a seed.
+pub fn seed() -> Result<u64, WithReturnCode<Error>> {
+    if let Some(seed) = seed_override() {
+        return Ok(seed);
+    }
+    let Json(seed) = unsafe { host::get_random_seed() }.map_err(|e| {
+        WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "impossible to get random seed: {}",
+                e
+            ))),
+            23,
+        )
+    })?;
+    Ok(seed)
+}
+
+/// Returns a new generator seeded with [`seed`]. Every call starts the same sequence.
+pub fn rng() -> Result<DeterministicRng, WithReturnCode<Error>> {
+    Ok(DeterministicRng::seed_from_u64(seed()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::distributions::Standard;
+
+    #[test]
+    fn test_seeded_rng_is_reproducible() {
+        set_seed(42);
+        assert_eq!(seed_override(), Some(42));
+
+        let first: Vec<f64> = DeterministicRng::seed_from_u64(42)
+            .sample_iter(Standard)
+            .take(5)
+            .collect();
+        let second: Vec<f64> = DeterministicRng::seed_from_u64(42)
+            .sample_iter(Standard)
+            .take(5)
+            .collect();
+        assert_eq!(first, second);
+        assert!(first.iter().all(|x| (0.0..1.0).contains(x)));
+
+        clear_seed();
+        assert_eq!(seed_override(), None);
+    }
+
+    #[test]
+    fn test_seed_override_is_thread_local() {
+        set_seed(7);
+        std::thread::spawn(|| {
+            assert_eq!(seed_override(), None);
+            set_seed(8);
+        })
+        .join()
+        .unwrap();
+        assert_eq!(seed_override(), Some(7));
+        clear_seed();
+    }
+}
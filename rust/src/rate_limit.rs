@@ -0,0 +1,120 @@
+//! Token-bucket rate limiting persisted in host [`state`](crate::state), so limits hold
+//! across invocations. Consult it before sending notifications or placing orders to keep
+//! a runaway strategy from hammering a channel or an exchange.
+use std::time::Duration;
+
+use extism_pdk::{Error, WithReturnCode};
+use serde::{Deserialize, Serialize};
+
+use crate::execution::now;
+use crate::state::{state_get, state_set};
+
+/// How many actions are allowed per period. Unused allowance accumulates up to `count`,
+/// so short bursts are allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rate {
+    pub count: u32,
+    pub period: Duration,
+}
+
+impl Rate {
+    pub fn new(count: u32, period: Duration) -> Self {
+        Self { count, period }
+    }
+
+    pub fn per_second(count: u32) -> Self {
+        Self::new(count, Duration::from_secs(1))
+    }
+
+    pub fn per_minute(count: u32) -> Self {
+        Self::new(count, Duration::from_secs(60))
+    }
+
+    pub fn per_hour(count: u32) -> Self {
+        Self::new(count, Duration::from_secs(3600))
+    }
+
+    /// Tokens regained per millisecond.
+    fn refill_rate(&self) -> f64 {
+        self.count as f64 / self.period.as_millis().max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Bucket {
+    tokens: f64,
+    /// Host time of the last update, in milliseconds.
+    updated_at: i64,
+}
+
+impl Bucket {
+    fn full(rate: Rate, now: i64) -> Self {
+        Self {
+            tokens: rate.count as f64,
+            updated_at: now,
+        }
+    }
+
+    /// Refills the bucket up to `now` and takes a token if one is available.
+    fn take(mut self, rate: Rate, now: i64) -> (bool, Self) {
+        let elapsed = (now - self.updated_at).max(0) as f64;
+        self.tokens = (self.tokens + elapsed * rate.refill_rate()).min(rate.count as f64);
+        self.updated_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            (true, self)
+        } else {
+            (false, self)
+        }
+    }
+}
+
+pub struct RateLimiter;
+
+impl RateLimiter {
+    /// Takes a token from the bucket `name`, returning `false` if the rate is exhausted.
+    ///
+    /// ```ignore
+    /// if RateLimiter::acquire("alerts", Rate::per_minute(5))? {
+    ///     schedule_webhook(url, &body)?;
+    /// }
+    /// ```
+    ///
+    /// Fails with the codes of [`now`] and [`state_get`]/[`state_set`].
+    pub fn acquire(name: &str, rate: Rate) -> Result<bool, WithReturnCode<Error>> {
+        let key = format!("rate_limit.{}", name);
+        let now = now()?;
+        let bucket = state_get::<Bucket>(&key)?.unwrap_or_else(|| Bucket::full(rate, now));
+        let (acquired, bucket) = bucket.take(rate, now);
+        // A bucket untouched for a whole period is full again, so the host may forget it.
+        state_set(&key, &bucket, Some(rate.period))?;
+        Ok(acquired)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_allows_burst_then_refills() {
+        let rate = Rate::per_minute(2);
+        let bucket = Bucket::full(rate, 0);
+
+        let (first, bucket) = bucket.take(rate, 0);
+        let (second, bucket) = bucket.take(rate, 1_000);
+        let (third, bucket) = bucket.take(rate, 2_000);
+        assert!(first && second);
+        assert!(!third);
+
+        // One token every 30 seconds.
+        let (early, bucket) = bucket.take(rate, 29_000);
+        assert!(!early);
+        let (refilled, bucket) = bucket.take(rate, 32_000);
+        assert!(refilled);
+
+        // Never accumulates more than the rate's count.
+        let (_, bucket) = bucket.take(rate, 10_000_000);
+        assert!((bucket.tokens - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,220 @@
+//! Local runner executing a function natively on a captured `FunctionArgs` payload, for a
+//! tight development loop without uploading the plugin to the platform.
+//!
+//! Host calls are served by the [`testing`](crate::testing) mocks. Add a binary to the
+//! plugin crate, with the `runner` feature enabled for it:
+//!
+//! ```ignore
+//! // src/bin/run.rs
+//! fn main() -> std::process::ExitCode {
+//!     exchange_outpost_abi::runner::main(my_plugin::run)
+//! }
+//! ```
+//!
+//! then run `cargo run --bin run -- fixtures/btc_1h.json --now 1700000000000 --secret api_key=...`.
+use std::fs::File;
+use std::io::Read;
+use std::process::ExitCode;
+
+use extism_pdk::{Error, WithReturnCode};
+use serde::Serialize;
+
+use crate::testing::{self, Notification};
+use crate::{FunctionArgs, logging::LogLevel};
+
+const USAGE: &str = "usage: run <fixture.json | -> [--now MS] [--seed N] [--secret NAME=VALUE]... [--config KEY=VALUE]...";
+
+/// Command line options of the runner.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunnerOptions {
+    /// Path of the JSON payload, `-` for standard input.
+    pub fixture: String,
+    pub now: Option<i64>,
+    pub seed: Option<u64>,
+    pub secrets: Vec<(String, String)>,
+    pub config: Vec<(String, String)>,
+}
+
+impl RunnerOptions {
+    /// Parses the options from command line arguments, without the program name.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = RunnerOptions::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+            match arg.as_str() {
+                "--now" => options.now = Some(parse(&value("--now")?)?),
+                "--seed" => options.seed = Some(parse(&value("--seed")?)?),
+                "--secret" => options.secrets.push(pair(&value("--secret")?)?),
+                "--config" => options.config.push(pair(&value("--config")?)?),
+                _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+                _ if options.fixture.is_empty() => options.fixture = arg,
+                _ => return Err(format!("unexpected argument {}", arg)),
+            }
+        }
+        if options.fixture.is_empty() {
+            return Err("missing fixture".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid number {}", value))
+}
+
+fn pair(value: &str) -> Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .ok_or(format!("expected NAME=VALUE, got {}", value))
+}
+
+/// What a function did during a local run.
+#[derive(Debug)]
+pub struct RunReport<O> {
+    pub result: Result<O, WithReturnCode<Error>>,
+    pub notifications: Vec<Notification>,
+    pub logs: Vec<(LogLevel, String)>,
+}
+
+/// Loads the fixture, configures the mocked host and runs `function`.
+///
+/// Fails if the fixture cannot be read or parsed.
+pub fn run<O>(
+    options: &RunnerOptions,
+    function: impl FnOnce(FunctionArgs) -> Result<O, WithReturnCode<Error>>,
+) -> Result<RunReport<O>, Error> {
+    let mut payload = String::new();
+    if options.fixture == "-" {
+        std::io::stdin().read_to_string(&mut payload)?;
+    } else {
+        File::open(&options.fixture)?.read_to_string(&mut payload)?;
+    }
+    let args = FunctionArgs::from_json_str(&payload)?;
+
+    testing::reset();
+    if let Some(now) = options.now {
+        testing::set_now(now);
+    }
+    if let Some(seed) = options.seed {
+        testing::set_random_seed(seed);
+    }
+    for (name, value) in &options.secrets {
+        testing::set_secret(name, value);
+    }
+    for (key, value) in &options.config {
+        testing::set_config(key, value);
+    }
+
+    let result = function(args);
+    Ok(RunReport {
+        result,
+        notifications: testing::notifications(),
+        logs: testing::logs(),
+    })
+}
+
+/// Entry point of a runner binary: runs `function` on the fixture given on the command
+/// line and prints its logs, notifications and output.
+///
+/// Exits with the return code of the function when it fails.
+pub fn main<O: Serialize>(
+    function: impl FnOnce(FunctionArgs) -> Result<O, WithReturnCode<Error>>,
+) -> ExitCode {
+    let options = match RunnerOptions::from_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let report = match run(&options, function) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("cannot load {}: {}", options.fixture, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    for (level, line) in &report.logs {
+        eprintln!("[{:?}] {}", level, line);
+    }
+    for notification in &report.notifications {
+        eprintln!(
+            "{} to {}: {}",
+            notification.kind, notification.target, notification.body
+        );
+    }
+    match report.result {
+        Ok(output) => match serde_json::to_string_pretty(&output) {
+            Ok(json) => {
+                println!("{}", json);
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("cannot serialize output: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+        Err(WithReturnCode(e, code)) => {
+            eprintln!("function failed with code {}: {}", code, e);
+            ExitCode::from(u8::try_from(code).unwrap_or(1))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_options_from_args() {
+        let options =
+            RunnerOptions::from_args(args("f.json --now 5 --secret key=a=b --config x=1")).unwrap();
+        assert_eq!(options.fixture, "f.json");
+        assert_eq!(options.now, Some(5));
+        assert_eq!(
+            options.secrets,
+            vec![("key".to_string(), "a=b".to_string())]
+        );
+        assert_eq!(options.config, vec![("x".to_string(), "1".to_string())]);
+
+        assert!(RunnerOptions::from_args(args("--now 5")).is_err());
+        assert!(RunnerOptions::from_args(args("f.json --now")).is_err());
+        assert!(RunnerOptions::from_args(args("f.json --verbose")).is_err());
+    }
+
+    #[cfg(feature = "state")]
+    #[test]
+    fn test_run_fixture() {
+        let path = std::env::temp_dir().join(format!("runner_fixture_{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{"tickers_data": {}, "piped_data": {}, "call_arguments": {"target": "me@example.com"}}"#,
+        )
+        .unwrap();
+        let options = RunnerOptions {
+            fixture: path.display().to_string(),
+            now: Some(42),
+            ..Default::default()
+        };
+        let report = run(&options, |args| {
+            let target: String = args.get_call_argument("target")?;
+            crate::state::state_set("target", &target, None)?;
+            crate::now()
+        })
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(report.result.unwrap(), 42);
+        assert_eq!(
+            testing::get_state::<String>("target").as_deref(),
+            Some("me@example.com")
+        );
+    }
+}
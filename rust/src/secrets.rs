@@ -0,0 +1,76 @@
+use std::fmt;
+
+use extism_pdk::{Error, WithReturnCode};
+
+#[cfg(any(not(feature = "test-utils"), target_arch = "wasm32"))]
+mod host {
+    pub use extism_pdk::config::get as config_get;
+}
+
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+mod host {
+    use crate::testing::host_call;
+    use extism_pdk::Error;
+
+    pub fn config_get(key: String) -> Result<Option<String>, Error> {
+        host_call("config_get", |host| Ok(host.config.get(&key).cloned()))
+    }
+}
+
+/// A secret value, redacted when formatted so it never ends up in logs or outputs by
+/// accident.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    /// The secret value, to pass to the API that needs it.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+/// Reads a secret configured by the user on the platform, e.g. a third-party API key.
+///
+/// Secrets are provided by the host through the plugin config under the `secret.` prefix,
+/// instead of call arguments which are visible in execution logs. Fails with return code
+/// 22 if the secret is not configured.
+pub fn get_secret(name: &str) -> Result<Secret, WithReturnCode<Error>> {
+    let not_found = |detail: String| {
+        WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "Secret {} not available{}",
+                name, detail
+            ))),
+            22,
+        )
+    };
+    host::config_get(format!("secret.{}", name))
+        .map_err(|e| not_found(format!(": {}", e)))?
+        .map(Secret)
+        .ok_or_else(|| not_found(String::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_is_redacted() {
+        let secret = Secret("api-key".to_string());
+        assert_eq!(format!("{:?}", secret), "Secret(***)");
+        assert_eq!(format!("{}", secret), "***");
+        assert_eq!(secret.expose(), "api-key");
+    }
+}
@@ -0,0 +1,236 @@
+const MINUTE_MS: i64 = 60_000;
+const HOUR_MS: i64 = 60 * MINUTE_MS;
+const DAY_MS: i64 = 24 * HOUR_MS;
+
+/// Daylight saving time rule of a [`TimeZone`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DstRule {
+    /// No daylight saving time.
+    None,
+    /// United States: from 02:00 local time on the second Sunday of March to 02:00 local
+    /// time on the first Sunday of November.
+    Us,
+    /// European Union and United Kingdom: from 01:00 UTC on the last Sunday of March to
+    /// 01:00 UTC on the last Sunday of October.
+    Eu,
+}
+
+/// Time zone given by its standard offset from UTC and its daylight saving time rule,
+/// one hour ahead of the standard offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeZone {
+    /// Offset from UTC outside of daylight saving time, in minutes.
+    pub offset_minutes: i32,
+    pub dst: DstRule,
+}
+
+impl TimeZone {
+    pub const UTC: TimeZone = TimeZone::fixed(0);
+    pub const NEW_YORK: TimeZone = TimeZone::new(-5 * 60, DstRule::Us);
+    pub const CHICAGO: TimeZone = TimeZone::new(-6 * 60, DstRule::Us);
+    pub const LONDON: TimeZone = TimeZone::new(0, DstRule::Eu);
+    pub const FRANKFURT: TimeZone = TimeZone::new(60, DstRule::Eu);
+    pub const TOKYO: TimeZone = TimeZone::fixed(9 * 60);
+    pub const HONG_KONG: TimeZone = TimeZone::fixed(8 * 60);
+
+    pub const fn new(offset_minutes: i32, dst: DstRule) -> Self {
+        TimeZone {
+            offset_minutes,
+            dst,
+        }
+    }
+
+    pub const fn fixed(offset_minutes: i32) -> Self {
+        TimeZone::new(offset_minutes, DstRule::None)
+    }
+
+    /// Offset from UTC at `timestamp`, in milliseconds.
+    pub fn offset_at(&self, timestamp: i64) -> i64 {
+        let standard = self.offset_minutes as i64 * MINUTE_MS;
+        let (year, _, _) = civil_from_days((timestamp + standard).div_euclid(DAY_MS));
+        let (start, end) = match self.dst {
+            DstRule::None => return standard,
+            DstRule::Us => (
+                days_from_civil(year, 3, 1) + 7 + days_to_sunday(year, 3, 1),
+                days_from_civil(year, 11, 1) + days_to_sunday(year, 11, 1),
+            ),
+            DstRule::Eu => (last_sunday(year, 3), last_sunday(year, 10)),
+        };
+        let (start, end) = match self.dst {
+            DstRule::Us => (
+                start * DAY_MS + 2 * HOUR_MS - standard,
+                end * DAY_MS + HOUR_MS - standard,
+            ),
+            _ => (start * DAY_MS + HOUR_MS, end * DAY_MS + HOUR_MS),
+        };
+        if (start..end).contains(&timestamp) {
+            standard + HOUR_MS
+        } else {
+            standard
+        }
+    }
+
+    /// `timestamp` shifted to local wall-clock time, still counted from the epoch.
+    pub fn to_local(&self, timestamp: i64) -> i64 {
+        timestamp + self.offset_at(timestamp)
+    }
+}
+
+/// Hours during which a market trades, in the local time of its exchange.
+///
+/// A session ending before it starts spans midnight, like most futures sessions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Session {
+    /// Start of the session, in minutes after local midnight.
+    pub start: u16,
+    /// End of the session (excluded), in minutes after local midnight.
+    pub end: u16,
+    pub time_zone: TimeZone,
+    /// Whether the session is closed on Saturdays and Sundays, in local time.
+    pub weekdays_only: bool,
+}
+
+impl Session {
+    /// Session from `start` to `end`, given as `(hour, minute)` in `time_zone`, every day
+    /// of the week.
+    pub const fn new(start: (u16, u16), end: (u16, u16), time_zone: TimeZone) -> Self {
+        Session {
+            start: start.0 * 60 + start.1,
+            end: end.0 * 60 + end.1,
+            time_zone,
+            weekdays_only: false,
+        }
+    }
+
+    /// Regular trading hours of US equities, 09:30 to 16:00 New York time on weekdays.
+    pub const fn rth() -> Self {
+        Session::new((9, 30), (16, 0), TimeZone::NEW_YORK).on_weekdays()
+    }
+
+    /// Closes the session on Saturdays and Sundays.
+    pub const fn on_weekdays(mut self) -> Self {
+        self.weekdays_only = true;
+        self
+    }
+
+    /// Whether the market is open at `timestamp`.
+    pub fn contains(&self, timestamp: i64) -> bool {
+        let local = self.time_zone.to_local(timestamp);
+        let days = local.div_euclid(DAY_MS);
+        let minute = (local.rem_euclid(DAY_MS) / MINUTE_MS) as u16;
+        if self.weekdays_only && weekday(days) >= 5 {
+            return false;
+        }
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+/// Day of the week of the day `days` after the epoch, from 0 for Monday to 6 for Sunday.
+pub(crate) fn weekday(days: i64) -> u8 {
+    // 1970-01-01 was a Thursday
+    (days + 3).rem_euclid(7) as u8
+}
+
+/// Days from the epoch to the given date of the proleptic Gregorian calendar.
+pub(crate) fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = year as i64 - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Date of the day `days` after the epoch, as `(year, month, day)`.
+pub(crate) fn civil_from_days(days: i64) -> (i32, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year as i32, month, day)
+}
+
+fn days_to_sunday(year: i32, month: u8, day: u8) -> i64 {
+    (6 - weekday(days_from_civil(year, month, day)) as i64).rem_euclid(7)
+}
+
+fn last_sunday(year: i32, month: u8) -> i64 {
+    let last = days_from_civil(year, month + 1, 1) - 1;
+    last - (weekday(last) as i64 + 1) % 7
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_days() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2024, 3, 10), 19_792);
+        assert_eq!(civil_from_days(19_792), (2024, 3, 10));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(weekday(days_from_civil(2024, 3, 10)), 6);
+    }
+
+    #[test]
+    fn test_dst_transitions() {
+        // 2024-03-10 06:59 and 07:00 UTC, around 02:00 in New York
+        assert_eq!(
+            TimeZone::NEW_YORK.offset_at(1_710_053_940_000),
+            -5 * HOUR_MS
+        );
+        assert_eq!(
+            TimeZone::NEW_YORK.offset_at(1_710_054_000_000),
+            -4 * HOUR_MS
+        );
+        // 2024-11-03 05:59 and 06:00 UTC
+        assert_eq!(
+            TimeZone::NEW_YORK.offset_at(1_730_613_540_000),
+            -4 * HOUR_MS
+        );
+        assert_eq!(
+            TimeZone::NEW_YORK.offset_at(1_730_613_600_000),
+            -5 * HOUR_MS
+        );
+        // 2024-03-31 01:00 and 2024-10-27 01:00 UTC
+        assert_eq!(TimeZone::LONDON.offset_at(1_711_846_800_000), HOUR_MS);
+        assert_eq!(TimeZone::LONDON.offset_at(1_729_990_800_000), 0);
+        assert_eq!(TimeZone::TOKYO.offset_at(1_711_846_800_000), 9 * HOUR_MS);
+    }
+
+    #[test]
+    fn test_rth_across_dst() {
+        let rth = Session::rth();
+        // Friday 2024-03-08 14:30 UTC is 09:30 in New York, before the change
+        assert!(rth.contains(1_709_908_200_000));
+        assert!(!rth.contains(1_709_908_200_000 - MINUTE_MS));
+        // Monday 2024-03-11 13:30 UTC is 09:30 in New York, after the change
+        assert!(rth.contains(1_710_163_800_000));
+        assert!(!rth.contains(1_710_163_800_000 - MINUTE_MS));
+        // 16:00 closes the session
+        assert!(!rth.contains(1_710_187_200_000));
+        // Saturday 2024-03-09 15:00 UTC
+        assert!(!rth.contains(1_709_996_400_000));
+    }
+
+    #[test]
+    fn test_overnight_session() {
+        let globex = Session::new((17, 0), (16, 0), TimeZone::CHICAGO);
+        // 2024-01-02 22:30 UTC is 16:30 in Chicago
+        assert!(!globex.contains(1_704_234_600_000));
+        // 2024-01-02 23:00 UTC is 17:00 in Chicago
+        assert!(globex.contains(1_704_236_400_000));
+        assert!(globex.contains(1_704_236_400_000 + 8 * HOUR_MS));
+    }
+}
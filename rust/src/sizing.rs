@@ -0,0 +1,97 @@
+//! Position sizing helpers.
+//!
+//! Sizes are returned as order quantities rounded down to the market lot step with
+//! [`MarketInfo::quantity_from_f64`], so they can be submitted as-is.
+use rust_decimal::Decimal;
+
+use crate::MarketInfo;
+
+/// Kelly criterion: the fraction of equity to risk given the probability of winning and
+/// the ratio between the average win and the average loss.
+///
+/// Negative edges yield zero. Strategies usually bet a fraction of the full Kelly
+/// fraction to reduce variance.
+pub fn kelly_fraction(win_rate: f64, win_loss_ratio: f64) -> f64 {
+    if win_loss_ratio <= 0.0 {
+        return 0.0;
+    }
+    (win_rate - (1.0 - win_rate) / win_loss_ratio).max(0.0)
+}
+
+/// Quantity that invests `fraction` of `equity` at `price`.
+pub fn fraction_size(equity: f64, fraction: f64, price: f64, market: &MarketInfo) -> Decimal {
+    if price <= 0.0 {
+        return Decimal::ZERO;
+    }
+    market.quantity_from_f64(equity * fraction / price)
+}
+
+/// Fixed-fractional sizing: the quantity that loses `risk_fraction` of `equity` if the
+/// price moves by `stop_distance` against the position, e.g. a multiple of the ATR.
+pub fn fixed_fractional(
+    equity: f64,
+    risk_fraction: f64,
+    stop_distance: f64,
+    market: &MarketInfo,
+) -> Decimal {
+    if stop_distance <= 0.0 {
+        return Decimal::ZERO;
+    }
+    market.quantity_from_f64(equity * risk_fraction / stop_distance)
+}
+
+/// Volatility targeting: the quantity whose exposure has a volatility of `target_volatility`
+/// relative to `equity`, given the asset volatility over the same horizon.
+pub fn volatility_target(
+    equity: f64,
+    target_volatility: f64,
+    asset_volatility: f64,
+    price: f64,
+    market: &MarketInfo,
+) -> Decimal {
+    if asset_volatility <= 0.0 {
+        return Decimal::ZERO;
+    }
+    fraction_size(equity, target_volatility / asset_volatility, price, market)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market() -> MarketInfo {
+        MarketInfo {
+            tick_size: Decimal::new(1, 2),
+            lot_step: Decimal::new(1, 3),
+            min_quantity: Decimal::new(1, 3),
+            min_notional: Decimal::ZERO,
+            max_leverage: None,
+        }
+    }
+
+    #[test]
+    fn test_kelly_fraction() {
+        assert!((kelly_fraction(0.6, 1.0) - 0.2).abs() < 1e-12);
+        assert_eq!(kelly_fraction(0.3, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_fixed_fractional() {
+        // Risking 1% of 10k with a 250 stop distance
+        assert_eq!(
+            fixed_fractional(10_000.0, 0.01, 250.0, &market()),
+            Decimal::new(4, 1)
+        );
+        assert_eq!(
+            fixed_fractional(10_000.0, 0.01, 0.0, &market()),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_volatility_target() {
+        // Half of the equity when the asset is twice as volatile as the target
+        let quantity = volatility_target(10_000.0, 0.2, 0.4, 3_000.0, &market());
+        assert_eq!(quantity, Decimal::new(1_666, 3));
+    }
+}
@@ -0,0 +1,149 @@
+//! Golden-output snapshots, to catch regressions in a strategy's output when refactoring
+//! indicator code.
+//!
+//! Outputs are serialized canonically, with sorted keys and floats written with a fixed
+//! number of decimals, so a snapshot only changes when the output really does. Set
+//! `UPDATE_GOLDEN=1` to rewrite the stored files after an intended change.
+//!
+//! ```ignore
+//! let output = run(FunctionArgs::from_json_str(FIXTURE)?)?;
+//! assert_golden("tests/golden/breakout.json", &output);
+//! ```
+use std::fmt::Write;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Decimals kept by [`assert_golden`] when writing floats.
+pub const DEFAULT_DECIMALS: usize = 8;
+
+/// Serializes `value` as indented JSON with sorted object keys and floats written with
+/// `decimals` decimals.
+///
+/// Panics if `value` cannot be represented as JSON, e.g. a map with non-string keys.
+pub fn canonical_json<T: Serialize>(value: &T, decimals: usize) -> String {
+    let value = serde_json::to_value(value).expect("snapshot value is not serializable");
+    let mut out = String::new();
+    write_value(&mut out, &value, decimals, 0);
+    out.push('\n');
+    out
+}
+
+fn write_value(out: &mut String, value: &Value, decimals: usize, indent: usize) {
+    match value {
+        Value::Number(n) if n.is_f64() => {
+            let f = n.as_f64().unwrap_or_default();
+            let formatted = format!("{:.*}", decimals, f);
+            // -0.000 and 0.000 are the same output
+            if formatted
+                .trim_start_matches('-')
+                .trim_matches(['0', '.'])
+                .is_empty()
+            {
+                let _ = write!(out, "{:.*}", decimals, 0.0);
+            } else {
+                out.push_str(&formatted);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            out.push_str("[\n");
+            for (i, item) in items.iter().enumerate() {
+                push_indent(out, indent + 1);
+                write_value(out, item, decimals, indent + 1);
+                out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+            }
+            push_indent(out, indent);
+            out.push(']');
+        }
+        Value::Object(map) if !map.is_empty() => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push_str("{\n");
+            for (i, (key, item)) in entries.iter().enumerate() {
+                push_indent(out, indent + 1);
+                let _ = write!(out, "{}: ", Value::String(key.to_string()));
+                write_value(out, item, decimals, indent + 1);
+                out.push_str(if i + 1 < entries.len() { ",\n" } else { "\n" });
+            }
+            push_indent(out, indent);
+            out.push('}');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    out.push_str(&"  ".repeat(indent));
+}
+
+/// Compares the canonical form of `value` with the golden file at `path`, panicking with
+/// the first difference.
+///
+/// The file is written instead when it does not exist yet or when the `UPDATE_GOLDEN`
+/// environment variable is set.
+#[track_caller]
+pub fn assert_golden<T: Serialize>(path: impl AsRef<Path>, value: &T) {
+    assert_golden_with(path, value, DEFAULT_DECIMALS)
+}
+
+/// Like [`assert_golden`], keeping `decimals` decimals for floats.
+#[track_caller]
+pub fn assert_golden_with<T: Serialize>(path: impl AsRef<Path>, value: &T, decimals: usize) {
+    let path = path.as_ref();
+    let actual = canonical_json(value, decimals);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() || !path.exists() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("cannot create golden file directory");
+        }
+        std::fs::write(path, &actual).expect("cannot write golden file");
+        return;
+    }
+    let expected = std::fs::read_to_string(path).expect("cannot read golden file");
+    if expected == actual {
+        return;
+    }
+    let line = expected
+        .lines()
+        .zip(actual.lines())
+        .position(|(e, a)| e != a)
+        .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+    panic!(
+        "output differs from golden file {} at line {}:\n  expected: {}\n  actual:   {}\nrerun with UPDATE_GOLDEN=1 to accept the new output",
+        path.display(),
+        line + 1,
+        expected.lines().nth(line).unwrap_or("<end of file>"),
+        actual.lines().nth(line).unwrap_or("<end of file>"),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonical_json() {
+        let value = json!({"b": [1, 0.1 + 0.2, -0.0000001], "a": {"z": null, "y": "s"}, "c": []});
+        assert_eq!(
+            canonical_json(&value, 3),
+            "{\n  \"a\": {\n    \"y\": \"s\",\n    \"z\": null\n  },\n  \"b\": [\n    1,\n    0.300,\n    0.000\n  ],\n  \"c\": []\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_assert_golden() {
+        let path = std::env::temp_dir()
+            .join(format!("exchange_outpost_golden_{}", std::process::id()))
+            .join("signals.json");
+        let _ = std::fs::remove_file(&path);
+        let signals = vec![(1_000, 0.5), (2_000, -1.25)];
+        assert_golden(&path, &signals);
+        assert_golden(&path, &signals);
+
+        let changed = vec![(1_000, 0.5), (2_000, -1.5)];
+        let result = std::panic::catch_unwind(|| assert_golden(&path, &changed));
+        let _ = std::fs::remove_file(&path);
+        assert!(result.is_err());
+    }
+}
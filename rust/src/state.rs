@@ -0,0 +1,154 @@
+//! Persistent key-value state kept by the host between invocations.
+//!
+//! Values are stored as JSON, so any serializable type can be remembered, e.g. the time
+//! of the last alert or an indicator state from [`indicators`](crate::indicators).
+//! Keys are scoped to the function by the host.
+use std::time::Duration;
+
+use extism_pdk::{Error, Json, WithReturnCode};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+#[cfg(any(not(feature = "test-utils"), target_arch = "wasm32"))]
+mod host {
+    use super::StateEntry;
+    use extism_pdk::{Json, host_fn};
+    use serde_json::Value;
+
+    #[host_fn]
+    extern "ExtismHost" {
+        pub fn state_get(key: String) -> Json<Option<Value>>;
+        pub fn state_set(entry: Json<StateEntry>);
+        pub fn state_delete(key: String);
+    }
+}
+
+// TTLs are not simulated: mocked values live until the test resets the host
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+mod host {
+    use super::StateEntry;
+    use crate::testing::host_call;
+    use extism_pdk::{Error, Json};
+    use serde_json::Value;
+
+    pub unsafe fn state_get(key: String) -> Result<Json<Option<Value>>, Error> {
+        host_call("state_get", |host| Ok(Json(host.state.get(&key).cloned())))
+    }
+
+    pub unsafe fn state_set(Json(entry): Json<StateEntry>) -> Result<(), Error> {
+        host_call("state_set", |host| {
+            host.state.insert(entry.key, entry.value);
+            Ok(())
+        })
+    }
+
+    pub unsafe fn state_delete(key: String) -> Result<(), Error> {
+        host_call("state_delete", |host| {
+            host.state.remove(&key);
+            Ok(())
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StateEntry {
+    key: String,
+    value: Value,
+    /// Seconds after which the host forgets the value, `None` to keep it forever.
+    ttl_seconds: Option<u64>,
+}
+
+fn host_error(action: &str, key: &str, e: impl std::fmt::Display) -> WithReturnCode<Error> {
+    WithReturnCode::new(
+        Error::new(std::io::Error::other(format!(
+            "impossible to {} state {}: {}",
+            action, key, e
+        ))),
+        16,
+    )
+}
+
+/// Reads a value, `None` if the key was never set or has expired.
+///
+/// Fails with return code 16 if the host cannot be reached and 17 if the stored value
+/// does not deserialize into `T`.
+pub fn state_get<T: DeserializeOwned>(key: &str) -> Result<Option<T>, WithReturnCode<Error>> {
+    let Json(value) =
+        unsafe { host::state_get(key.to_string()) }.map_err(|e| host_error("read", key, e))?;
+    value
+        .map(|value| {
+            serde_json::from_value(value).map_err(|e| {
+                WithReturnCode::new(
+                    Error::new(std::io::Error::other(format!(
+                        "Failed to parse state {}: {}",
+                        key, e
+                    ))),
+                    17,
+                )
+            })
+        })
+        .transpose()
+}
+
+// The host counts TTLs in whole seconds: round up, so a sub-second TTL is not 0
+fn ttl_seconds(ttl: Duration) -> u64 {
+    let seconds = ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0);
+    seconds.max(1)
+}
+
+/// Stores a value, replacing any previous one. With a `ttl`, the host forgets the value
+/// once it has elapsed, rounded up to whole seconds and at least one second.
+///
+/// Fails with return code 16 if the value cannot be serialized or stored.
+pub fn state_set<T: Serialize>(
+    key: &str,
+    value: &T,
+    ttl: Option<Duration>,
+) -> Result<(), WithReturnCode<Error>> {
+    let entry = StateEntry {
+        key: key.to_string(),
+        value: serde_json::to_value(value).map_err(|e| host_error("serialize", key, e))?,
+        ttl_seconds: ttl.map(ttl_seconds),
+    };
+    unsafe { host::state_set(Json(entry)) }.map_err(|e| host_error("write", key, e))
+}
+
+/// Removes a value.
+pub fn state_delete(key: &str) -> Result<(), WithReturnCode<Error>> {
+    unsafe { host::state_delete(key.to_string()) }.map_err(|e| host_error("delete", key, e))
+}
+
+/// Returns the value stored under `key`, or runs `compute` and stores its result for
+/// `ttl`. Useful to memoize expensive work, such as an HTTP fetch or a correlation matrix,
+/// across frequent invocations.
+///
+/// A stored value that no longer deserializes into `T` is recomputed. Errors from
+/// `compute` are returned as is and nothing is stored.
+pub fn cached<T, F>(key: &str, ttl: Duration, compute: F) -> Result<T, WithReturnCode<Error>>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, WithReturnCode<Error>>,
+{
+    match state_get(key) {
+        Ok(Some(value)) => return Ok(value),
+        Ok(None) => {}
+        Err(e) if e.1 == 17 => {}
+        Err(e) => return Err(e),
+    }
+    let value = compute()?;
+    state_set(key, &value, Some(ttl))?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_rounds_up() {
+        assert_eq!(ttl_seconds(Duration::from_millis(500)), 1);
+        assert_eq!(ttl_seconds(Duration::from_millis(1_500)), 2);
+        assert_eq!(ttl_seconds(Duration::from_secs(60)), 60);
+        assert_eq!(ttl_seconds(Duration::ZERO), 1);
+    }
+}
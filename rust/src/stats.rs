@@ -0,0 +1,353 @@
+//! Performance statistics over returns and equity series.
+//!
+//! Returns are simple per-period returns (`0.01` for +1%). Annualized figures take the
+//! number of periods in a year, e.g. `365.0` for daily crypto candles or `252.0` for
+//! daily equity candles.
+use serde::Serialize;
+
+/// Simple per-period returns of an equity curve.
+pub fn returns_from_equity(equity: &[f64]) -> Vec<f64> {
+    equity.windows(2).map(|w| w[1] / w[0] - 1.0).collect()
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Annualized Sharpe ratio, using the sample standard deviation of the excess returns.
+///
+/// `risk_free` is the risk-free rate per period. Returns `None` for fewer than two
+/// returns or a zero standard deviation.
+pub fn sharpe_ratio(returns: &[f64], risk_free: f64, periods_per_year: f64) -> Option<f64> {
+    if returns.len() < 2 {
+        return None;
+    }
+    let excess: Vec<f64> = returns.iter().map(|r| r - risk_free).collect();
+    let avg = mean(&excess)?;
+    let var = excess.iter().map(|r| (r - avg).powi(2)).sum::<f64>() / (excess.len() - 1) as f64;
+    let std = var.sqrt();
+    (std > 0.0).then(|| avg / std * periods_per_year.sqrt())
+}
+
+/// Annualized Sortino ratio, penalizing only returns below `risk_free`.
+///
+/// Returns `None` when there are no returns or no downside deviation.
+pub fn sortino_ratio(returns: &[f64], risk_free: f64, periods_per_year: f64) -> Option<f64> {
+    let excess: Vec<f64> = returns.iter().map(|r| r - risk_free).collect();
+    let avg = mean(&excess)?;
+    let downside = excess.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / excess.len() as f64;
+    let downside = downside.sqrt();
+    (downside > 0.0).then(|| avg / downside * periods_per_year.sqrt())
+}
+
+/// Largest peak-to-trough decline of an equity curve, as a positive fraction of the peak.
+pub fn max_drawdown(equity: &[f64]) -> f64 {
+    let mut peak = f64::NEG_INFINITY;
+    let mut max_dd: f64 = 0.0;
+    for &value in equity {
+        peak = peak.max(value);
+        if peak > 0.0 {
+            max_dd = max_dd.max((peak - value) / peak);
+        }
+    }
+    max_dd
+}
+
+/// Compound annual growth rate of an equity curve sampled once per period.
+///
+/// Returns `None` for fewer than two points or a non-positive starting equity.
+pub fn cagr(equity: &[f64], periods_per_year: f64) -> Option<f64> {
+    let (&first, &last) = (equity.first()?, equity.last()?);
+    if equity.len() < 2 || first <= 0.0 {
+        return None;
+    }
+    let years = (equity.len() - 1) as f64 / periods_per_year;
+    Some((last / first).powf(1.0 / years) - 1.0)
+}
+
+/// Fraction of strictly positive values, e.g. over per-trade returns.
+pub fn win_rate(returns: &[f64]) -> Option<f64> {
+    (!returns.is_empty())
+        .then(|| returns.iter().filter(|&&r| r > 0.0).count() as f64 / returns.len() as f64)
+}
+
+/// Sample covariance (`n - 1` denominator) of two equally long series.
+///
+/// Returns `None` for series shorter than two values or of different lengths.
+pub fn covariance(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() != b.len() || a.len() < 2 {
+        return None;
+    }
+    let (mean_a, mean_b) = (mean(a)?, mean(b)?);
+    let sum: f64 = a
+        .iter()
+        .zip(b)
+        .map(|(x, y)| (x - mean_a) * (y - mean_b))
+        .sum();
+    Some(sum / (a.len() - 1) as f64)
+}
+
+/// Pearson correlation of two equally long series, `None` if either has no variance.
+pub fn correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    let denominator = (covariance(a, a)? * covariance(b, b)?).sqrt();
+    if denominator > 0.0 {
+        Some(covariance(a, b)? / denominator)
+    } else {
+        None
+    }
+}
+
+/// Beta of an asset against a benchmark: the covariance of their returns divided by
+/// the variance of the benchmark returns.
+pub fn beta(asset: &[f64], benchmark: &[f64]) -> Option<f64> {
+    let variance = covariance(benchmark, benchmark)?;
+    if variance > 0.0 {
+        Some(covariance(asset, benchmark)? / variance)
+    } else {
+        None
+    }
+}
+
+/// Covariance matrix of equally long series. Undefined entries are `NaN`.
+pub fn covariance_matrix(series: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    pairwise(series, covariance)
+}
+
+/// Correlation matrix of equally long series. Undefined entries are `NaN`.
+pub fn correlation_matrix(series: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    pairwise(series, correlation)
+}
+
+fn pairwise(series: &[Vec<f64>], f: impl Fn(&[f64], &[f64]) -> Option<f64>) -> Vec<Vec<f64>> {
+    series
+        .iter()
+        .map(|a| series.iter().map(|b| f(a, b).unwrap_or(f64::NAN)).collect())
+        .collect()
+}
+
+/// Value at Risk and Expected Shortfall, as positive fractions of the position value.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RiskEstimate {
+    /// Loss not exceeded with the requested confidence.
+    pub var: f64,
+    /// Average loss when the VaR is exceeded.
+    pub expected_shortfall: f64,
+}
+
+/// Historical VaR and ES over `horizon` periods at a `confidence` level such as `0.95`.
+///
+/// The quantiles are taken from the empirical returns and scaled to the horizon with the
+/// square root of time. Returns `None` for an empty series or a confidence outside `(0, 1)`.
+pub fn historical_var(returns: &[f64], confidence: f64, horizon: usize) -> Option<RiskEstimate> {
+    if returns.is_empty() || confidence <= 0.0 || confidence >= 1.0 {
+        return None;
+    }
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let index = (((1.0 - confidence) * sorted.len() as f64).floor() as usize).min(sorted.len() - 1);
+    let tail = &sorted[..=index];
+    let scale = (horizon.max(1) as f64).sqrt();
+    Some(RiskEstimate {
+        var: -sorted[index] * scale,
+        expected_shortfall: -mean(tail)? * scale,
+    })
+}
+
+/// Parametric (normal) VaR and ES over `horizon` periods at a `confidence` level such as
+/// `0.95`, from the mean and sample standard deviation of the returns.
+///
+/// Returns `None` for fewer than two returns or a confidence outside `(0, 1)`.
+pub fn parametric_var(returns: &[f64], confidence: f64, horizon: usize) -> Option<RiskEstimate> {
+    if returns.len() < 2 || confidence <= 0.0 || confidence >= 1.0 {
+        return None;
+    }
+    let avg = mean(returns)?;
+    let std = covariance(returns, returns)?.sqrt();
+    let horizon = horizon.max(1) as f64;
+    let (mu, sigma) = (avg * horizon, std * horizon.sqrt());
+    let z = inverse_normal_cdf(confidence);
+    let density = (-z * z / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt();
+    Some(RiskEstimate {
+        var: z * sigma - mu,
+        expected_shortfall: sigma * density / (1.0 - confidence) - mu,
+    })
+}
+
+// Acklam's rational approximation of the standard normal quantile, accurate to ~1e-9
+fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < 0.02425 {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - 0.02425 {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Standard metrics of an equity curve.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PerformanceStats {
+    pub total_return: f64,
+    pub cagr: Option<f64>,
+    pub sharpe: Option<f64>,
+    pub sortino: Option<f64>,
+    pub max_drawdown: f64,
+    /// Fraction of periods with a positive return.
+    pub win_rate: Option<f64>,
+}
+
+impl PerformanceStats {
+    /// Computes the metrics of an equity curve sampled once per period, with a zero
+    /// risk-free rate.
+    pub fn from_equity(equity: &[f64], periods_per_year: f64) -> Self {
+        let returns = returns_from_equity(equity);
+        let total_return = match (equity.first(), equity.last()) {
+            (Some(&first), Some(&last)) if first != 0.0 => last / first - 1.0,
+            _ => 0.0,
+        };
+        PerformanceStats {
+            total_return,
+            cagr: cagr(equity, periods_per_year),
+            sharpe: sharpe_ratio(&returns, 0.0, periods_per_year),
+            sortino: sortino_ratio(&returns, 0.0, periods_per_year),
+            max_drawdown: max_drawdown(equity),
+            win_rate: win_rate(&returns),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_max_drawdown() {
+        assert!(approx(
+            max_drawdown(&[100.0, 120.0, 90.0, 130.0, 117.0]),
+            0.25
+        ));
+        assert_eq!(max_drawdown(&[1.0, 2.0, 3.0]), 0.0);
+    }
+
+    #[test]
+    fn test_cagr() {
+        // Doubling over two years of daily points
+        let equity: Vec<f64> = (0..=730).map(|i| 2f64.powf(i as f64 / 730.0)).collect();
+        assert!(approx(cagr(&equity, 365.0).unwrap(), 2f64.sqrt() - 1.0));
+        assert_eq!(cagr(&[1.0], 365.0), None);
+    }
+
+    #[test]
+    fn test_sharpe_sortino() {
+        let returns = [0.01, -0.02, 0.03, 0.0];
+        let sharpe = sharpe_ratio(&returns, 0.0, 1.0).unwrap();
+        // mean 0.005, sample std sqrt(0.00043333)
+        assert!(approx(sharpe, 0.005 / (0.0013f64 / 3.0).sqrt()));
+        let sortino = sortino_ratio(&returns, 0.0, 1.0).unwrap();
+        assert!(approx(sortino, 0.005 / 0.01));
+        assert_eq!(sortino_ratio(&[0.01, 0.02], 0.0, 1.0), None);
+        assert_eq!(sharpe_ratio(&[0.01, 0.01], 0.0, 1.0), None);
+    }
+
+    #[test]
+    fn test_win_rate() {
+        assert_eq!(win_rate(&[0.1, -0.1, 0.0, 0.2]), Some(0.5));
+        assert_eq!(win_rate(&[]), None);
+    }
+
+    #[test]
+    fn test_covariance_correlation() {
+        let a = [1.0, 2.0, 3.0];
+        let b = [3.0, 2.0, 1.0];
+        assert_eq!(covariance(&a, &b), Some(-1.0));
+        assert!(approx(correlation(&a, &b).unwrap(), -1.0));
+        assert_eq!(correlation(&a, &[1.0, 1.0, 1.0]), None);
+        let matrix = correlation_matrix(&[a.to_vec(), b.to_vec()]);
+        assert!(approx(matrix[0][0], 1.0));
+        assert!(approx(matrix[0][1], -1.0));
+    }
+
+    #[test]
+    fn test_beta() {
+        let benchmark = [0.01, -0.02, 0.03];
+        let asset: Vec<f64> = benchmark.iter().map(|r| 2.0 * r).collect();
+        assert!(approx(beta(&asset, &benchmark).unwrap(), 2.0));
+        assert_eq!(beta(&asset, &[0.0, 0.0, 0.0]), None);
+    }
+
+    #[test]
+    fn test_inverse_normal_cdf() {
+        assert!((inverse_normal_cdf(0.5)).abs() < 1e-9);
+        assert!((inverse_normal_cdf(0.95) - 1.644853627).abs() < 1e-6);
+        assert!((inverse_normal_cdf(0.01) + 2.326347874).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_historical_var() {
+        let returns: Vec<f64> = (1..=100).map(|i| i as f64 / 1000.0 - 0.05).collect();
+        let risk = historical_var(&returns, 0.95, 1).unwrap();
+        // Sixth worst return of 100 is -0.044, the tail averages the six worst
+        assert!(approx(risk.var, 0.044));
+        assert!(approx(risk.expected_shortfall, 0.0465));
+        let scaled = historical_var(&returns, 0.95, 4).unwrap();
+        assert!(approx(scaled.var, 0.088));
+        assert_eq!(historical_var(&returns, 1.0, 1), None);
+    }
+
+    #[test]
+    fn test_parametric_var() {
+        let returns = [0.01, -0.01, 0.01, -0.01];
+        let risk = parametric_var(&returns, 0.95, 1).unwrap();
+        let std = (0.0004f64 / 3.0).sqrt();
+        assert!((risk.var - 1.644853627 * std).abs() < 1e-8);
+        assert!(risk.expected_shortfall > risk.var);
+    }
+
+    #[test]
+    fn test_performance_stats() {
+        let stats = PerformanceStats::from_equity(&[100.0, 110.0, 99.0, 121.0], 252.0);
+        assert!(approx(stats.total_return, 0.21));
+        assert!(approx(stats.max_drawdown, 0.1));
+        assert!(stats.sharpe.is_some());
+    }
+}
@@ -0,0 +1,197 @@
+//! `proptest` strategies for the inputs of a function, so plugins can property-test their
+//! parsing and indicator code against weird inputs.
+//!
+//! The values are biased towards edge cases: zero, tiny, huge and negative prices, high
+//! below low, empty series, duplicated or unordered timestamps, and call arguments sent
+//! as strings. Failing cases shrink like any other proptest input.
+//!
+//! ```ignore
+//! use exchange_outpost_abi::strategies;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn never_panics(args in strategies::function_args(100)) {
+//!         let _ = run(args);
+//!     }
+//! }
+//! ```
+use proptest::prelude::*;
+use serde_json::{Value, json};
+
+use crate::{Candle, FunctionArgs, TickersData};
+
+/// Prices, including zero, tiny, huge and negative ones.
+pub fn price() -> impl Strategy<Value = f64> {
+    prop_oneof![
+        1 => Just(0.0),
+        1 => 1e-12..1e-6,
+        1 => 1e9..1e15,
+        1 => -100.0..0.0,
+        6 => 0.01..100_000.0,
+    ]
+}
+
+/// Timestamps in milliseconds, including zero and negative ones.
+pub fn timestamp() -> impl Strategy<Value = i64> {
+    prop_oneof![
+        1 => Just(0),
+        1 => -1_000_000_000_000i64..0,
+        8 => 0..4_000_000_000_000i64,
+    ]
+}
+
+/// A candle whose fields are drawn independently, so they need not be consistent.
+pub fn candle() -> impl Strategy<Value = Candle<f64>> {
+    (timestamp(), price(), price(), price(), price(), price()).prop_map(
+        |(timestamp, open, high, low, close, volume)| Candle {
+            timestamp,
+            open,
+            high,
+            low,
+            close,
+            volume: volume.abs(),
+        },
+    )
+}
+
+impl Arbitrary for Candle<f64> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        candle().boxed()
+    }
+}
+
+/// Up to `max_len` candles, usually with increasing or repeated timestamps one minute
+/// apart, sometimes in any order.
+pub fn candles(max_len: usize) -> impl Strategy<Value = Vec<Candle<f64>>> {
+    let ordered = (
+        timestamp(),
+        prop::collection::vec((candle(), 0..3i64), 0..=max_len),
+    )
+        .prop_map(|(start, steps)| {
+            let mut timestamp = start;
+            steps
+                .into_iter()
+                .map(|(candle, step)| {
+                    timestamp += step * 60_000;
+                    Candle {
+                        timestamp,
+                        ..candle
+                    }
+                })
+                .collect()
+        });
+    prop_oneof![
+        3 => prop::collection::vec(candle(), 0..=max_len),
+        7 => ordered,
+    ]
+}
+
+/// A ticker as sent by the host, with up to `max_len` candles.
+pub fn tickers_data_json(max_len: usize) -> impl Strategy<Value = Value> {
+    (candles(max_len), 0..12i32).prop_map(|(candles, precision)| {
+        let candles: Vec<Value> = candles
+            .iter()
+            .map(|c| json!([c.timestamp, c.open, c.high, c.low, c.close, c.volume]))
+            .collect();
+        json!({
+            "symbol": "SYNTH",
+            "exchange": "proptest",
+            "candles": candles,
+            "precision": precision,
+        })
+    })
+}
+
+/// A ticker loaded like the ones of the function arguments.
+pub fn tickers_data(max_len: usize) -> impl Strategy<Value = TickersData> {
+    tickers_data_json(max_len)
+        .prop_map(|json| serde_json::from_value(json).expect("generated ticker does not parse"))
+}
+
+/// A full function payload as sent by the host, with up to three tickers and call
+/// arguments of various types, numbers sometimes sent as strings.
+pub fn function_args_json(max_len: usize) -> impl Strategy<Value = String> {
+    (
+        prop::collection::vec(tickers_data_json(max_len), 0..=3),
+        0..100u32,
+        prop::bool::weighted(0.1),
+        price(),
+        any::<(bool, bool)>(),
+    )
+        .prop_map(|(tickers, period, period_as_string, threshold, flags)| {
+            let tickers: serde_json::Map<String, Value> = tickers
+                .into_iter()
+                .enumerate()
+                .map(|(i, ticker)| (format!("t{}", i), ticker))
+                .collect();
+            let period = if period_as_string {
+                json!(period.to_string())
+            } else {
+                json!(period)
+            };
+            json!({
+                "tickers_data": tickers,
+                "piped_data": {},
+                "call_arguments": {
+                    "period": period,
+                    "threshold": threshold,
+                    "label": "t0",
+                    "flags": [flags.0, flags.1],
+                },
+            })
+            .to_string()
+        })
+}
+
+/// Function arguments parsed from [`function_args_json`].
+pub fn function_args(max_len: usize) -> impl Strategy<Value = FunctionArgs> {
+    function_args_json(max_len).prop_map(|json| {
+        FunctionArgs::from_json_str(&json).expect("generated function args do not parse")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_function_args_parse(args in function_args(50)) {
+            for label in args.get_labels() {
+                let candles = args.get_candles(label).unwrap();
+                prop_assert!(candles.len() <= 50);
+                #[cfg(feature = "decimal")]
+                let _ = args.get_candles_decimal(label).unwrap();
+            }
+            prop_assert!(args.get_call_argument::<u32>("period").unwrap() < 100);
+        }
+
+        #[test]
+        fn test_arbitrary_candle(candle in any::<Candle<f64>>()) {
+            prop_assert!(candle.volume >= 0.0);
+        }
+
+        #[test]
+        fn test_tickers_data_timestamps(data in tickers_data(20)) {
+            prop_assert!(data.timestamp_unit.is_some() || data.candles.is_empty());
+        }
+
+        #[cfg(feature = "indicators")]
+        #[test]
+        fn test_indicators_survive_arbitrary_candles(candles in candles(100)) {
+            use crate::indicators::{Atr, Ema, Indicator, Mfi, Obv, Rsi};
+
+            Ema::new(10).compute(&candles);
+            Rsi::new(14).compute(&candles);
+            Atr::new(14).compute(&candles);
+            Obv::new().compute(&candles);
+            Mfi::new(14).compute(&candles);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! Streaming invocation mode, for latency-sensitive strategies that should not re-parse
+//! their whole history on every bar.
+//!
+//! The host starts the stream once with the usual [`FunctionArgs`] payload, then pushes
+//! every new candle, trade or order fill as an [`Update`]. The SDK keeps the arguments up
+//! to date between pushes and hands them to the [`StreamFunction`] along with the update.
+//!
+//! ```ignore
+//! struct Breakout { ema: Ema }
+//!
+//! impl StreamFunction for Breakout {
+//!     type Output = Signal;
+//!
+//!     fn init(args: &FunctionArgs) -> Result<Self, AbiError> { ... }
+//!
+//!     fn on_update(&mut self, args: &FunctionArgs, update: &Update) -> Result<Option<Signal>, AbiError> { ... }
+//! }
+//!
+//! exchange_outpost_abi::export_stream!(Breakout);
+//! ```
+//!
+//! This exports `stream_init`, taking the `FunctionArgs` payload, and `stream_push`,
+//! taking one update and returning the output of the function, or an empty output when
+//! the update produced nothing.
+//!
+//! The last candle of a ticker is pushed again each time it changes while its bar forms.
+//! The built-in indicators ignore a timestamp they already processed, so wrap them in
+//! [`FormingBar`](crate::indicators::FormingBar) to follow every version of the bar.
+use serde::{Deserialize, Serialize};
+
+use crate::{AbiError, Candle, FunctionArgs, TimestampUnit};
+
+/// An incremental update pushed by the host.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Update {
+    /// A new candle of the ticker `label`, or a new version of its last candle while it is
+    /// still open, with its timestamp in the unit of the ticker. The unit of a ticker
+    /// started without candles is detected from its first pushed candle.
+    Candle { label: String, candle: Candle<f64> },
+    /// A trade printed on the market of the ticker `label`.
+    Trade {
+        label: String,
+        timestamp: i64,
+        price: f64,
+        quantity: f64,
+    },
+    /// A fill of one of the user's orders.
+    Fill {
+        order_id: String,
+        symbol: String,
+        timestamp: i64,
+        price: f64,
+        /// Signed quantity, positive for buys and negative for sells.
+        quantity: f64,
+    },
+}
+
+/// A function running in streaming mode.
+pub trait StreamFunction: Sized + 'static {
+    /// The value returned to the host after an update.
+    type Output: Serialize;
+
+    /// Most candles kept per ticker, dropping the oldest ones, so memory stays bounded
+    /// however long the stream runs. `None` keeps every candle.
+    const MAX_CANDLES: Option<usize> = None;
+
+    /// Sets the function up from the initial arguments, e.g. warming up its indicators on
+    /// the history.
+    fn init(args: &FunctionArgs) -> Result<Self, AbiError>;
+
+    /// Handles an update, already applied to `args`, returning `None` when there is
+    /// nothing to report.
+    fn on_update(
+        &mut self,
+        args: &FunctionArgs,
+        update: &Update,
+    ) -> Result<Option<Self::Output>, AbiError>;
+}
+
+/// A running stream: the arguments kept up to date and the function fed with updates.
+pub struct Stream<S> {
+    args: FunctionArgs,
+    function: S,
+    max_candles: Option<usize>,
+}
+
+impl<S: StreamFunction> Stream<S> {
+    /// Starts the stream, keeping at most [`S::MAX_CANDLES`](StreamFunction::MAX_CANDLES)
+    /// candles per ticker.
+    pub fn start(args: FunctionArgs) -> Result<Self, AbiError> {
+        let function = S::init(&args)?;
+        Ok(Stream {
+            args,
+            function,
+            max_candles: S::MAX_CANDLES,
+        })
+    }
+
+    /// Keeps at most `max` candles per ticker, dropping the oldest ones, so memory stays
+    /// bounded however long the stream runs. Overrides
+    /// [`S::MAX_CANDLES`](StreamFunction::MAX_CANDLES).
+    pub fn with_max_candles(mut self, max: usize) -> Self {
+        self.max_candles = Some(max);
+        self
+    }
+
+    pub fn args(&self) -> &FunctionArgs {
+        &self.args
+    }
+
+    pub fn function(&self) -> &S {
+        &self.function
+    }
+
+    /// Applies `update` to the arguments and feeds it to the function.
+    ///
+    /// Fails with code 3 when a candle is pushed for an unknown ticker.
+    pub fn push(&mut self, update: Update) -> Result<Option<S::Output>, AbiError> {
+        if let Update::Candle { label, candle } = &update {
+            let ticker = self.args.get_ticker_mut(label)?;
+            // Tickers started without candles get the unit of their first pushed candle
+            let unit = *ticker
+                .timestamp_unit
+                .get_or_insert_with(|| TimestampUnit::detect(candle.timestamp));
+            ticker.push_candle(Candle {
+                timestamp: unit.to_millis(candle.timestamp),
+                ..*candle
+            });
+            if let Some(max) = self.max_candles {
+                let excess = ticker.candles.len().saturating_sub(max);
+                ticker.candles.drain(..excess);
+            }
+        }
+        self.function.on_update(&self.args, &update)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct LastClose {
+        updates: usize,
+    }
+
+    impl StreamFunction for LastClose {
+        type Output = f64;
+
+        fn init(_: &FunctionArgs) -> Result<Self, AbiError> {
+            Ok(LastClose { updates: 0 })
+        }
+
+        fn on_update(
+            &mut self,
+            args: &FunctionArgs,
+            update: &Update,
+        ) -> Result<Option<f64>, AbiError> {
+            self.updates += 1;
+            match update {
+                Update::Candle { label, .. } => {
+                    Ok(args.get_candles(label)?.last().map(|c| c.close))
+                }
+                _ => Ok(None),
+            }
+        }
+    }
+
+    fn update(json: &str) -> Update {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_stream_applies_updates() {
+        let args = FunctionArgs::from_json_str(
+            r#"{"tickers_data": {"btc": {"symbol": "BTCUSDT", "exchange": "binance", "precision": 2,
+                "candles": [[1700000000000, 1, 1, 1, 1, 1]]}},
+                "piped_data": {}, "call_arguments": {}}"#,
+        )
+        .unwrap();
+        let mut stream = Stream::<LastClose>::start(args)
+            .unwrap()
+            .with_max_candles(2);
+
+        let partial =
+            r#"{"type": "candle", "label": "btc", "candle": [1700000060000, 1, 2, 1, 2, 1]}"#;
+        assert_eq!(stream.push(update(partial)).unwrap(), Some(2.0));
+        let closed =
+            r#"{"type": "candle", "label": "btc", "candle": [1700000060000, 1, 3, 1, 3, 2]}"#;
+        assert_eq!(stream.push(update(closed)).unwrap(), Some(3.0));
+        assert_eq!(stream.args().get_candles("btc").unwrap().len(), 2);
+        let next =
+            r#"{"type": "candle", "label": "btc", "candle": [1700000120000, 3, 4, 3, 4, 1]}"#;
+        stream.push(update(next)).unwrap();
+        let candles = stream.args().get_candles("btc").unwrap();
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].timestamp, 1_700_000_060_000);
+
+        let fill = r#"{"type": "fill", "order_id": "1", "symbol": "BTCUSDT", "timestamp": 1, "price": 4, "quantity": 0.5}"#;
+        assert_eq!(stream.push(update(fill)).unwrap(), None);
+        assert_eq!(stream.function().updates, 4);
+
+        let unknown =
+            r#"{"type": "candle", "label": "eth", "candle": [1700000120000, 3, 4, 3, 4, 1]}"#;
+        assert_eq!(stream.push(update(unknown)).unwrap_err().1, 3);
+    }
+
+    struct Bounded;
+
+    impl StreamFunction for Bounded {
+        type Output = f64;
+        const MAX_CANDLES: Option<usize> = Some(1);
+
+        fn init(_: &FunctionArgs) -> Result<Self, AbiError> {
+            Ok(Bounded)
+        }
+
+        fn on_update(&mut self, _: &FunctionArgs, _: &Update) -> Result<Option<f64>, AbiError> {
+            Ok(None)
+        }
+    }
+
+    fn empty_ticker() -> FunctionArgs {
+        FunctionArgs::from_json_str(
+            r#"{"tickers_data": {"aapl": {"symbol": "AAPL", "exchange": "nasdaq", "precision": 2,
+                "candles": []}},
+                "piped_data": {}, "call_arguments": {}}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_max_candles_from_function() {
+        let mut stream = Stream::<Bounded>::start(empty_ticker()).unwrap();
+        for timestamp in [1_700_000_000, 1_700_000_060] {
+            let candle = format!(
+                r#"{{"type": "candle", "label": "aapl", "candle": [{}, 1, 1, 1, 1, 1]}}"#,
+                timestamp
+            );
+            stream.push(update(&candle)).unwrap();
+        }
+        let ticker = stream.args().get_ticker("aapl").unwrap();
+        assert_eq!(ticker.candles.len(), 1);
+        // The unit is detected from the first pushed candle and kept
+        assert_eq!(ticker.timestamp_unit, Some(TimestampUnit::Seconds));
+        assert_eq!(ticker.candles[0].timestamp, 1_700_000_060_000);
+    }
+
+    #[cfg(feature = "indicators")]
+    #[test]
+    fn test_forming_bar_then_final() {
+        use crate::indicators::{Ema, FormingBar, Indicator};
+
+        struct LastEma {
+            ema: FormingBar<Ema>,
+        }
+
+        impl StreamFunction for LastEma {
+            type Output = f64;
+
+            fn init(_: &FunctionArgs) -> Result<Self, AbiError> {
+                Ok(LastEma {
+                    ema: FormingBar::new(Ema::new(1)),
+                })
+            }
+
+            fn on_update(
+                &mut self,
+                args: &FunctionArgs,
+                update: &Update,
+            ) -> Result<Option<f64>, AbiError> {
+                match update {
+                    Update::Candle { label, .. } => {
+                        let last = args.get_candles(label)?.last().cloned();
+                        Ok(last.and_then(|candle| self.ema.update(&candle)))
+                    }
+                    _ => Ok(None),
+                }
+            }
+        }
+
+        let mut stream = Stream::<LastEma>::start(empty_ticker()).unwrap();
+        let forming =
+            r#"{"type": "candle", "label": "aapl", "candle": [1700000000, 1, 2, 1, 2, 1]}"#;
+        assert_eq!(stream.push(update(forming)).unwrap(), Some(2.0));
+        let last = r#"{"type": "candle", "label": "aapl", "candle": [1700000000, 1, 3, 1, 3, 2]}"#;
+        assert_eq!(stream.push(update(last)).unwrap(), Some(3.0));
+        assert_eq!(stream.function().ema.inner().value(), Some(3.0));
+    }
+}
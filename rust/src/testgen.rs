@@ -0,0 +1,218 @@
+//! Synthetic candle series for strategy tests and benchmarks, so they do not depend on
+//! bundling real exchange data.
+//!
+//! Series are generated from a seed, so the same generator always produces the same
+//! candles.
+//!
+//! ```ignore
+//! let candles = CandleGenerator::new(PriceModel::Gbm)
+//!     .with_trend(0.001)
+//!     .with_volatility(0.02)
+//!     .with_gap_probability(0.05)
+//!     .generate(500);
+//! ```
+//!
+//! To property-test parsing and indicator code against deliberately unusual inputs, use
+//! the strategies of the `strategies` module, behind the `proptest` feature.
+use rand::{Rng, SeedableRng};
+
+use crate::Candle;
+use crate::random::DeterministicRng;
+
+/// How the close moves from one candle to the next.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceModel {
+    /// Additive steps: the close moves by `trend + volatility * z` times the start price,
+    /// with `z` standard normal. Prices may become negative with a high volatility.
+    RandomWalk,
+    /// Geometric Brownian motion: log returns of `trend + volatility * z`, so prices stay
+    /// positive.
+    Gbm,
+    /// Replays the given returns in a loop, e.g. a daily or weekly cycle, on top of the
+    /// trend and volatility of a geometric model.
+    Seasonal(Vec<f64>),
+}
+
+/// Builds synthetic candle series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandleGenerator {
+    model: PriceModel,
+    seed: u64,
+    start_timestamp: i64,
+    interval_ms: i64,
+    start_price: f64,
+    trend: f64,
+    volatility: f64,
+    volume: f64,
+    gap_probability: f64,
+}
+
+impl CandleGenerator {
+    /// A generator of hourly candles starting at 100, without trend and with 1%
+    /// volatility per candle.
+    pub fn new(model: PriceModel) -> Self {
+        CandleGenerator {
+            model,
+            seed: 0,
+            start_timestamp: 0,
+            interval_ms: 3_600_000,
+            start_price: 100.0,
+            trend: 0.0,
+            volatility: 0.01,
+            volume: 1_000.0,
+            gap_probability: 0.0,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Timestamp of the first candle and spacing between candles, in milliseconds.
+    pub fn with_timing(mut self, start_timestamp: i64, interval_ms: i64) -> Self {
+        self.start_timestamp = start_timestamp;
+        self.interval_ms = interval_ms;
+        self
+    }
+
+    pub fn with_start_price(mut self, price: f64) -> Self {
+        self.start_price = price;
+        self
+    }
+
+    /// Expected return per candle.
+    pub fn with_trend(mut self, trend: f64) -> Self {
+        self.trend = trend;
+        self
+    }
+
+    /// Standard deviation of the return per candle.
+    pub fn with_volatility(mut self, volatility: f64) -> Self {
+        self.volatility = volatility;
+        self
+    }
+
+    /// Average volume per candle.
+    pub fn with_volume(mut self, volume: f64) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Probability that a candle is missing from the series, as when an exchange has no
+    /// trades or data was lost. The price keeps moving during the gap.
+    pub fn with_gap_probability(mut self, probability: f64) -> Self {
+        self.gap_probability = probability;
+        self
+    }
+
+    /// Generates the candles of `periods` consecutive intervals, minus the gaps.
+    pub fn generate(&self, periods: usize) -> Vec<Candle<f64>> {
+        let mut rng = DeterministicRng::seed_from_u64(self.seed);
+        let mut close = self.start_price;
+        let mut candles = Vec::with_capacity(periods);
+        for i in 0..periods {
+            let open = close;
+            let shock = self.volatility * standard_normal(&mut rng);
+            close = match &self.model {
+                PriceModel::RandomWalk => open + (self.trend + shock) * self.start_price,
+                PriceModel::Gbm => open * (self.trend + shock).exp(),
+                PriceModel::Seasonal(pattern) => {
+                    let season = match pattern.len() {
+                        0 => 0.0,
+                        len => pattern[i % len],
+                    };
+                    open * (self.trend + season + shock).exp()
+                }
+            };
+            // Wicks extend beyond the body by a fraction of the volatility
+            let wick = self.volatility * open.abs();
+            let high = open.max(close) + wick * rng.gen_range(0.0..0.5);
+            let low = open.min(close) - wick * rng.gen_range(0.0..0.5);
+            // |z| averages sqrt(2 / pi), so the volume averages the configured one
+            let volume = self.volume * (1.0 + standard_normal(&mut rng).abs())
+                / (1.0 + std::f64::consts::FRAC_2_PI.sqrt());
+            if rng.gen_bool(self.gap_probability.clamp(0.0, 1.0)) {
+                continue;
+            }
+            candles.push(Candle {
+                timestamp: self.start_timestamp + i as i64 * self.interval_ms,
+                open,
+                high,
+                low,
+                close,
+                volume,
+            });
+        }
+        candles
+    }
+}
+
+// Box-Muller transform, as rand does not ship distributions without rand_distr
+fn standard_normal(rng: &mut DeterministicRng) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen_range(0.0..1.0);
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closes(candles: &[Candle<f64>]) -> Vec<f64> {
+        candles.iter().map(|c| c.close).collect()
+    }
+
+    #[test]
+    fn test_same_seed_same_series() {
+        let generator = CandleGenerator::new(PriceModel::Gbm).with_seed(7);
+        assert_eq!(
+            closes(&generator.generate(50)),
+            closes(&generator.generate(50))
+        );
+        let other = generator.clone().with_seed(8);
+        assert_ne!(closes(&generator.generate(50)), closes(&other.generate(50)));
+    }
+
+    #[test]
+    fn test_candles_are_consistent() {
+        let candles = CandleGenerator::new(PriceModel::RandomWalk)
+            .with_timing(1_000, 60_000)
+            .generate(200);
+        assert_eq!(candles.len(), 200);
+        assert_eq!(candles[1].timestamp, 61_000);
+        for pair in candles.windows(2) {
+            assert_eq!(pair[1].open, pair[0].close);
+        }
+        for c in &candles {
+            assert!(c.high >= c.open.max(c.close));
+            assert!(c.low <= c.open.min(c.close));
+            assert!(c.volume > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_trend_and_gaps() {
+        let candles = CandleGenerator::new(PriceModel::Gbm)
+            .with_trend(0.01)
+            .with_volatility(0.001)
+            .generate(100);
+        assert!(candles.last().unwrap().close > 2.0 * 100.0);
+
+        let gapped = CandleGenerator::new(PriceModel::Gbm)
+            .with_gap_probability(0.3)
+            .generate(1_000);
+        assert!(gapped.len() > 600 && gapped.len() < 800);
+    }
+
+    #[test]
+    fn test_seasonal_pattern() {
+        let candles = CandleGenerator::new(PriceModel::Seasonal(vec![0.1, -0.1]))
+            .with_volatility(0.0)
+            .generate(4);
+        let closes = closes(&candles);
+        assert!((closes[0] - 100.0 * 0.1f64.exp()).abs() < 1e-9);
+        assert!((closes[1] - 100.0).abs() < 1e-9);
+        assert!((closes[3] - 100.0).abs() < 1e-9);
+    }
+}
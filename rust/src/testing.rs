@@ -0,0 +1,602 @@
+//! In-memory mocks of every host call, so functions can be tested natively with
+//! `cargo test`, without an Extism host.
+//!
+//! Enable the `test-utils` feature in `[dev-dependencies]` only: with it, native builds of
+//! the crate no longer call the real host. Wasm builds always call the real host, even
+//! when the feature ends up enabled by another dependency. The mocked host is thread
+//! local, so every test starts from an empty host. Configure the responses with the
+//! setters of this module, run the function, then inspect what it did or use the
+//! assertion macros.
+//!
+//! ```ignore
+//! use exchange_outpost_abi::{assert_webhook_scheduled, testing};
+//!
+//! #[test]
+//! fn alerts_on_breakout() {
+//!     testing::set_now(1_700_000_000_000);
+//!     run(load_fixture("breakout.json")).unwrap();
+//!     assert_webhook_scheduled!("https://hooks.example.com/alerts", "breakout");
+//! }
+//! ```
+use std::cell::RefCell;
+#[cfg(feature = "http")]
+use std::collections::BTreeMap;
+#[cfg(feature = "trading")]
+use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet};
+
+use extism_pdk::Error;
+#[cfg(any(feature = "http", feature = "state"))]
+use serde::Serialize;
+#[cfg(feature = "state")]
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+#[cfg(feature = "history")]
+use serde_json::json;
+
+use crate::ExecutionContext;
+#[cfg(feature = "logging")]
+use crate::logging::LogLevel;
+#[cfg(feature = "history")]
+use crate::{Candle, Timeframe};
+#[cfg(feature = "trading")]
+use crate::{
+    ExecutionMode,
+    orders::OrderRequest,
+    trading::{Balance, OrderAck, OrderChanges, Position},
+};
+
+/// A notification scheduled by the function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// `"webhook"` or `"email"`.
+    pub kind: String,
+    pub target: String,
+    pub body: String,
+}
+
+/// An HTTP request sent by the function.
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: Option<Vec<u8>>,
+}
+
+#[derive(Default)]
+pub(crate) struct MockHost {
+    failing: HashSet<String>,
+    pub(crate) notifications: Vec<Notification>,
+    #[cfg(feature = "state")]
+    pub(crate) state: HashMap<String, Value>,
+    #[cfg(feature = "http")]
+    pub(crate) http_responses: HashMap<(String, String), (u16, Vec<u8>)>,
+    #[cfg(feature = "http")]
+    pub(crate) http_requests: Vec<RecordedRequest>,
+    pub(crate) now: i64,
+    pub(crate) execution_context: Option<ExecutionContext>,
+    pub(crate) config: HashMap<String, String>,
+    pub(crate) random_seed: u64,
+    #[cfg(feature = "history")]
+    pub(crate) candles: HashMap<(String, String, Timeframe), Value>,
+    pub(crate) functions: HashMap<String, String>,
+    pub(crate) function_calls: Vec<(String, Value)>,
+    #[cfg(feature = "logging")]
+    pub(crate) logs: Vec<(LogLevel, String)>,
+    #[cfg(feature = "trading")]
+    pub(crate) placed_orders: Vec<(ExecutionMode, OrderRequest)>,
+    #[cfg(feature = "trading")]
+    pub(crate) canceled_orders: Vec<(ExecutionMode, String)>,
+    #[cfg(feature = "trading")]
+    pub(crate) modified_orders: Vec<(ExecutionMode, String, OrderChanges)>,
+    #[cfg(feature = "trading")]
+    pub(crate) order_acks: VecDeque<OrderAck>,
+    #[cfg(feature = "trading")]
+    pub(crate) positions: Vec<Position>,
+    #[cfg(feature = "trading")]
+    pub(crate) balances: Vec<Balance>,
+}
+
+thread_local! {
+    static HOST: RefCell<MockHost> = RefCell::new(MockHost::default());
+}
+
+// Runs a mocked host call, failing it if the test asked for it
+pub(crate) fn host_call<R>(
+    name: &str,
+    f: impl FnOnce(&mut MockHost) -> Result<R, Error>,
+) -> Result<R, Error> {
+    HOST.with(|host| {
+        let mut host = host.borrow_mut();
+        if host.failing.contains(name) {
+            return Err(Error::msg(format!("mocked host call {} failed", name)));
+        }
+        f(&mut host)
+    })
+}
+
+fn with_host<R>(f: impl FnOnce(&mut MockHost) -> R) -> R {
+    HOST.with(|host| f(&mut host.borrow_mut()))
+}
+
+/// Clears everything recorded and configured on this thread's mocked host.
+pub fn reset() {
+    with_host(|host| *host = MockHost::default());
+}
+
+/// Makes every call to the host function `name` (e.g. `"state_get"`) fail, to exercise
+/// error paths.
+pub fn fail_host_call(name: &str) {
+    with_host(|host| host.failing.insert(name.to_string()));
+}
+
+/// Sets the time returned by [`now`](crate::now), in milliseconds since the Unix epoch.
+pub fn set_now(timestamp: i64) {
+    with_host(|host| host.now = timestamp);
+}
+
+/// Sets the context returned by [`execution_context`](crate::execution_context), which
+/// fails until one is set.
+pub fn set_execution_context(context: ExecutionContext) {
+    with_host(|host| host.execution_context = Some(context));
+}
+
+/// Sets a plugin config value.
+pub fn set_config(key: &str, value: &str) {
+    with_host(|host| host.config.insert(key.to_string(), value.to_string()));
+}
+
+/// Sets a secret returned by [`get_secret`](crate::get_secret).
+pub fn set_secret(name: &str, value: &str) {
+    set_config(&format!("secret.{}", name), value);
+}
+
+/// Sets the seed provided by the host to [`rng`](crate::random::rng).
+pub fn set_random_seed(seed: u64) {
+    with_host(|host| host.random_seed = seed);
+}
+
+/// Answers `method` requests to `url` with `status` and a raw `body`. Requests without a
+/// mocked response fail as if the host could not send them.
+#[cfg(feature = "http")]
+pub fn mock_http(method: &str, url: &str, status: u16, body: &str) {
+    with_host(|host| {
+        host.http_responses.insert(
+            (method.to_uppercase(), url.to_string()),
+            (status, body.as_bytes().to_vec()),
+        )
+    });
+}
+
+/// Answers `method` requests to `url` with `status` and `body` encoded as JSON.
+#[cfg(feature = "http")]
+pub fn mock_http_json<T: Serialize>(method: &str, url: &str, status: u16, body: &T) {
+    let body = serde_json::to_string(body).expect("mocked HTTP body is not serializable");
+    mock_http(method, url, status, &body);
+}
+
+/// HTTP requests sent so far, in order.
+#[cfg(feature = "http")]
+pub fn http_requests() -> Vec<RecordedRequest> {
+    with_host(|host| host.http_requests.clone())
+}
+
+/// Notifications scheduled so far, in order.
+pub fn notifications() -> Vec<Notification> {
+    with_host(|host| host.notifications.clone())
+}
+
+/// Webhooks scheduled so far, in order.
+pub fn webhooks() -> Vec<Notification> {
+    notifications()
+        .into_iter()
+        .filter(|n| n.kind == "webhook")
+        .collect()
+}
+
+/// Emails scheduled so far, in order.
+pub fn emails() -> Vec<Notification> {
+    notifications()
+        .into_iter()
+        .filter(|n| n.kind == "email")
+        .collect()
+}
+
+/// Stores a value in the mocked host state, as if set by a previous invocation.
+#[cfg(feature = "state")]
+pub fn set_state<T: Serialize>(key: &str, value: &T) {
+    let value = serde_json::to_value(value).expect("mocked state is not serializable");
+    with_host(|host| host.state.insert(key.to_string(), value));
+}
+
+/// Reads a value from the mocked host state.
+#[cfg(feature = "state")]
+pub fn get_state<T: DeserializeOwned>(key: &str) -> Option<T> {
+    with_host(|host| host.state.get(key).cloned())
+        .map(|value| serde_json::from_value(value).expect("mocked state has another type"))
+}
+
+/// Serves `candles` to [`fetch_candles`](crate::fetch_candles) for the given market and
+/// timeframe, filtered by the requested range.
+#[cfg(feature = "history")]
+pub fn mock_candles(symbol: &str, exchange: &str, timeframe: Timeframe, candles: &[Candle<f64>]) {
+    let candles: Vec<Value> = candles
+        .iter()
+        .map(|c| json!([c.timestamp, c.open, c.high, c.low, c.close, c.volume]))
+        .collect();
+    let data = json!({
+        "symbol": symbol,
+        "exchange": exchange,
+        "candles": candles,
+        "timestamp_unit": "millis",
+        "precision": 8,
+    });
+    with_host(|host| {
+        host.candles
+            .insert((symbol.to_string(), exchange.to_string(), timeframe), data)
+    });
+}
+
+/// Makes [`call_function`](crate::call_function) return `output` for the function `name`.
+pub fn mock_function(name: &str, output: &str) {
+    with_host(|host| host.functions.insert(name.to_string(), output.to_string()));
+}
+
+/// Names and call arguments of the functions called so far, in order.
+pub fn function_calls() -> Vec<(String, Value)> {
+    with_host(|host| host.function_calls.clone())
+}
+
+/// Messages logged so far at any level, in order.
+#[cfg(feature = "logging")]
+pub fn logs() -> Vec<(LogLevel, String)> {
+    with_host(|host| host.logs.clone())
+}
+
+/// Makes the next order call return `ack` instead of a filled order.
+#[cfg(feature = "trading")]
+pub fn queue_order_ack(ack: OrderAck) {
+    with_host(|host| host.order_acks.push_back(ack));
+}
+
+/// Orders placed so far in any mode, in order.
+#[cfg(feature = "trading")]
+pub fn placed_orders() -> Vec<OrderRequest> {
+    with_host(|host| host.placed_orders.iter().map(|(_, o)| o.clone()).collect())
+}
+
+/// Orders placed so far in `mode`, in order, e.g. to check that a paper invocation never
+/// sends a live order.
+#[cfg(feature = "trading")]
+pub fn placed_orders_in(mode: ExecutionMode) -> Vec<OrderRequest> {
+    with_host(|host| {
+        host.placed_orders
+            .iter()
+            .filter(|(m, _)| *m == mode)
+            .map(|(_, o)| o.clone())
+            .collect()
+    })
+}
+
+/// Ids of the orders canceled so far in any mode, in order.
+#[cfg(feature = "trading")]
+pub fn canceled_orders() -> Vec<String> {
+    with_host(|host| {
+        host.canceled_orders
+            .iter()
+            .map(|(_, id)| id.clone())
+            .collect()
+    })
+}
+
+/// Ids of the orders canceled so far in `mode`, in order.
+#[cfg(feature = "trading")]
+pub fn canceled_orders_in(mode: ExecutionMode) -> Vec<String> {
+    with_host(|host| {
+        host.canceled_orders
+            .iter()
+            .filter(|(m, _)| *m == mode)
+            .map(|(_, id)| id.clone())
+            .collect()
+    })
+}
+
+/// Ids and changes of the orders modified so far in any mode, in order.
+#[cfg(feature = "trading")]
+pub fn modified_orders() -> Vec<(String, OrderChanges)> {
+    with_host(|host| {
+        host.modified_orders
+            .iter()
+            .map(|(_, id, changes)| (id.clone(), changes.clone()))
+            .collect()
+    })
+}
+
+/// Ids and changes of the orders modified so far in `mode`, in order.
+#[cfg(feature = "trading")]
+pub fn modified_orders_in(mode: ExecutionMode) -> Vec<(String, OrderChanges)> {
+    with_host(|host| {
+        host.modified_orders
+            .iter()
+            .filter(|(m, _, _)| *m == mode)
+            .map(|(_, id, changes)| (id.clone(), changes.clone()))
+            .collect()
+    })
+}
+
+/// Sets the positions returned by [`get_open_positions`](crate::trading::get_open_positions).
+#[cfg(feature = "trading")]
+pub fn set_positions(positions: Vec<Position>) {
+    with_host(|host| host.positions = positions);
+}
+
+/// Sets the balances returned by [`get_balances`](crate::trading::get_balances).
+#[cfg(feature = "trading")]
+pub fn set_balances(balances: Vec<Balance>) {
+    with_host(|host| host.balances = balances);
+}
+
+/// Panics unless a notification of `kind` was scheduled for `target`, with a body
+/// containing `body` if given. Used by [`assert_webhook_scheduled!`](crate::assert_webhook_scheduled)
+/// and [`assert_email_scheduled!`](crate::assert_email_scheduled).
+#[track_caller]
+pub fn assert_notification(kind: &str, target: &str, body: Option<&str>) {
+    let scheduled = notifications();
+    let found = scheduled.iter().any(|n| {
+        n.kind == kind && n.target == target && body.is_none_or(|body| n.body.contains(body))
+    });
+    assert!(
+        found,
+        "no {} scheduled for {}{}, scheduled: {:?}",
+        kind,
+        target,
+        body.map(|b| format!(" with body containing {:?}", b))
+            .unwrap_or_default(),
+        scheduled
+    );
+}
+
+/// Asserts that a webhook was scheduled for a path, optionally with a body containing a
+/// string.
+#[macro_export]
+macro_rules! assert_webhook_scheduled {
+    ($path:expr) => {
+        $crate::testing::assert_notification("webhook", $path, None)
+    };
+    ($path:expr, $body:expr) => {
+        $crate::testing::assert_notification("webhook", $path, Some($body))
+    };
+}
+
+/// Asserts that an email was scheduled for an address, optionally with a body containing
+/// a string.
+#[macro_export]
+macro_rules! assert_email_scheduled {
+    ($email:expr) => {
+        $crate::testing::assert_notification("email", $email, None)
+    };
+    ($email:expr, $body:expr) => {
+        $crate::testing::assert_notification("email", $email, Some($body))
+    };
+}
+
+/// Asserts that no notification was scheduled.
+#[macro_export]
+macro_rules! assert_no_notifications {
+    () => {
+        assert_eq!($crate::testing::notifications(), vec![])
+    };
+}
+
+/// Asserts that an order was placed for a symbol, optionally on a given side and in a
+/// given [`ExecutionMode`](crate::ExecutionMode).
+#[cfg(feature = "trading")]
+#[macro_export]
+macro_rules! assert_order_placed {
+    ($symbol:expr) => {
+        assert!(
+            $crate::testing::placed_orders()
+                .iter()
+                .any(|order| order.symbol == $symbol),
+            "no order placed for {}, placed: {:?}",
+            $symbol,
+            $crate::testing::placed_orders()
+        )
+    };
+    ($symbol:expr, $side:expr) => {
+        assert!(
+            $crate::testing::placed_orders()
+                .iter()
+                .any(|order| order.symbol == $symbol && order.side == $side),
+            "no {:?} order placed for {}, placed: {:?}",
+            $side,
+            $symbol,
+            $crate::testing::placed_orders()
+        )
+    };
+    ($symbol:expr, $side:expr, $mode:expr) => {
+        assert!(
+            $crate::testing::placed_orders_in($mode)
+                .iter()
+                .any(|order| order.symbol == $symbol && order.side == $side),
+            "no {:?} order placed for {} in {:?} mode, placed: {:?}",
+            $side,
+            $symbol,
+            $mode,
+            $crate::testing::placed_orders()
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    #[cfg(feature = "notifications")]
+    use crate::{schedule_email, schedule_webhook};
+
+    #[cfg(feature = "notifications")]
+    #[test]
+    fn test_notifications_are_recorded() {
+        reset();
+        assert_no_notifications!();
+        schedule_webhook("https://hooks.example.com/a", "price crossed 100").unwrap();
+        schedule_email("me@example.com", "hello").unwrap();
+        assert_webhook_scheduled!("https://hooks.example.com/a");
+        assert_webhook_scheduled!("https://hooks.example.com/a", "crossed");
+        assert_email_scheduled!("me@example.com");
+        assert_eq!(webhooks().len(), 1);
+
+        fail_host_call("add_notification");
+        assert_eq!(schedule_webhook("x", "y").unwrap_err().1, 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "no webhook scheduled")]
+    fn test_missing_webhook_panics() {
+        reset();
+        assert_webhook_scheduled!("https://hooks.example.com/a");
+    }
+
+    #[test]
+    fn test_time() {
+        reset();
+        set_now(60_000);
+        assert_eq!(crate::now().unwrap(), 60_000);
+    }
+
+    #[cfg(feature = "state")]
+    #[test]
+    fn test_state() {
+        use std::time::Duration;
+
+        use crate::state::{cached, state_get, state_set};
+        use crate::{Rate, RateLimiter};
+
+        reset();
+        set_now(60_000);
+        state_set("count", &3, None).unwrap();
+        assert_eq!(state_get::<i32>("count").unwrap(), Some(3));
+        assert_eq!(get_state::<i32>("count"), Some(3));
+
+        let mut computed = 0;
+        for _ in 0..2 {
+            let value = cached("expensive", Duration::from_secs(60), || {
+                computed += 1;
+                Ok(42)
+            })
+            .unwrap();
+            assert_eq!(value, 42);
+        }
+        assert_eq!(computed, 1);
+
+        assert!(RateLimiter::acquire("alerts", Rate::per_minute(1)).unwrap());
+        assert!(!RateLimiter::acquire("alerts", Rate::per_minute(1)).unwrap());
+    }
+
+    #[cfg(feature = "logging")]
+    #[test]
+    fn test_log_facade() {
+        reset();
+        let _ = crate::logging::init();
+        log::warn!(symbol = "BTCUSDT"; "spread {}", 2);
+        assert_eq!(
+            logs(),
+            vec![(LogLevel::Warn, "spread 2 symbol=BTCUSDT".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_secrets() {
+        reset();
+        set_secret("api_key", "k");
+        assert_eq!(crate::get_secret("api_key").unwrap().expose(), "k");
+        assert_eq!(crate::get_secret("missing").unwrap_err().1, 22);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_http() {
+        reset();
+        mock_http_json(
+            "GET",
+            "https://api.example.com/p",
+            200,
+            &json!({"price": 1}),
+        );
+        let value: Value = crate::http::HttpClient::new()
+            .with_header("X-Api-Key", "k")
+            .get_json("https://api.example.com/p")
+            .unwrap();
+        assert_eq!(value["price"], 1);
+        assert_eq!(http_requests()[0].headers["X-Api-Key"], "k");
+        assert_eq!(
+            crate::http::get_json::<Value>("https://api.example.com/other")
+                .unwrap_err()
+                .1,
+            18
+        );
+    }
+
+    #[cfg(feature = "history")]
+    #[test]
+    fn test_candles() {
+        reset();
+        let candles: Vec<Candle<f64>> = (0..5)
+            .map(|i| Candle {
+                timestamp: i * 1000,
+                open: 1.0,
+                high: 1.0,
+                low: 1.0,
+                close: 1.0,
+                volume: 1.0,
+            })
+            .collect();
+        mock_candles("BTCUSDT", "binance", Timeframe::hours(1), &candles);
+        let data =
+            crate::fetch_candles("BTCUSDT", "binance", Timeframe::hours(1), 1000..3000).unwrap();
+        assert_eq!(data.candles.len(), 2);
+        assert!(crate::fetch_candles("BTCUSDT", "binance", Timeframe::days(1), 0..1).is_err());
+    }
+
+    #[test]
+    fn test_functions() {
+        reset();
+        mock_function("signal", r#"{"value": 1}"#);
+        let output = crate::call_function("signal", &json!({"window": 3})).unwrap();
+        assert_eq!(output.parse::<Value>().unwrap()["value"], 1);
+        assert_eq!(function_calls()[0].1["window"], 3);
+    }
+
+    #[cfg(feature = "trading")]
+    #[test]
+    fn test_orders_are_recorded() {
+        use crate::ExecutionMode;
+        use crate::orders::OrderSide;
+        use crate::trading::{OrderStatus, cancel_order, place_order};
+        use rust_decimal::Decimal;
+
+        reset();
+        let order = OrderRequest::market("BTCUSDT", "binance", OrderSide::Buy, Decimal::ONE);
+        let ack = place_order(ExecutionMode::Paper, &order).unwrap();
+        assert_eq!(ack.status, OrderStatus::Filled);
+        assert_order_placed!("BTCUSDT", OrderSide::Buy);
+        assert_order_placed!("BTCUSDT", OrderSide::Buy, ExecutionMode::Paper);
+        assert!(placed_orders_in(ExecutionMode::Live).is_empty());
+        cancel_order(ExecutionMode::Live, &ack.order_id).unwrap();
+        assert_eq!(
+            canceled_orders_in(ExecutionMode::Live),
+            vec![ack.order_id.clone()]
+        );
+        assert!(canceled_orders_in(ExecutionMode::Paper).is_empty());
+
+        queue_order_ack(OrderAck {
+            status: OrderStatus::Rejected,
+            ..ack
+        });
+        assert_eq!(place_order(ExecutionMode::Paper, &order).unwrap_err().1, 12);
+    }
+}
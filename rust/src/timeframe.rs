@@ -0,0 +1,238 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// 1970-01-01 was a Thursday, weekly candles open on Mondays like on most exchanges
+const WEEK_OFFSET_MS: i64 = 4 * 86_400_000;
+
+/// Unit of a [`Timeframe`]. Months are not supported, as their length varies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TimeframeUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+}
+
+impl TimeframeUnit {
+    fn as_seconds(self) -> i64 {
+        match self {
+            TimeframeUnit::Second => 1,
+            TimeframeUnit::Minute => 60,
+            TimeframeUnit::Hour => 3_600,
+            TimeframeUnit::Day => 86_400,
+            TimeframeUnit::Week => 604_800,
+        }
+    }
+
+    fn suffix(self) -> char {
+        match self {
+            TimeframeUnit::Second => 's',
+            TimeframeUnit::Minute => 'm',
+            TimeframeUnit::Hour => 'h',
+            TimeframeUnit::Day => 'd',
+            TimeframeUnit::Week => 'w',
+        }
+    }
+}
+
+/// Interval of a candle series, written like `"1m"`, `"4h"` or `"1d"`.
+///
+/// Candles are aligned on the Unix epoch in UTC, except weekly candles which open on
+/// Mondays. All timestamps are in milliseconds, like candle timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timeframe {
+    pub count: u32,
+    pub unit: TimeframeUnit,
+}
+
+impl Timeframe {
+    pub const fn new(count: u32, unit: TimeframeUnit) -> Self {
+        Timeframe { count, unit }
+    }
+
+    pub const fn minutes(count: u32) -> Self {
+        Timeframe::new(count, TimeframeUnit::Minute)
+    }
+
+    pub const fn hours(count: u32) -> Self {
+        Timeframe::new(count, TimeframeUnit::Hour)
+    }
+
+    pub const fn days(count: u32) -> Self {
+        Timeframe::new(count, TimeframeUnit::Day)
+    }
+
+    /// The largest unit expressing `millis` exactly, `None` if it is not a whole number
+    /// of seconds.
+    pub fn from_millis(millis: i64) -> Option<Self> {
+        if millis <= 0 || millis % 1000 != 0 {
+            return None;
+        }
+        let seconds = millis / 1000;
+        [
+            TimeframeUnit::Week,
+            TimeframeUnit::Day,
+            TimeframeUnit::Hour,
+            TimeframeUnit::Minute,
+            TimeframeUnit::Second,
+        ]
+        .into_iter()
+        .find(|unit| seconds % unit.as_seconds() == 0)
+        .and_then(|unit| {
+            let count = u32::try_from(seconds / unit.as_seconds()).ok()?;
+            Some(Timeframe::new(count, unit))
+        })
+    }
+
+    pub fn as_seconds(&self) -> i64 {
+        self.count as i64 * self.unit.as_seconds()
+    }
+
+    pub fn as_millis(&self) -> i64 {
+        self.as_seconds() * 1000
+    }
+
+    fn offset(&self) -> i64 {
+        match self.unit {
+            TimeframeUnit::Week => WEEK_OFFSET_MS,
+            _ => 0,
+        }
+    }
+
+    /// Open time of the candle containing `timestamp`.
+    pub fn floor_timestamp(&self, timestamp: i64) -> i64 {
+        let step = self.as_millis();
+        (timestamp - self.offset()).div_euclid(step) * step + self.offset()
+    }
+
+    /// Open time of the first candle starting at or after `timestamp`.
+    pub fn ceil_timestamp(&self, timestamp: i64) -> i64 {
+        let floor = self.floor_timestamp(timestamp);
+        if floor == timestamp {
+            floor
+        } else {
+            floor + self.as_millis()
+        }
+    }
+
+    /// Whether `timestamp` is the open time of a candle.
+    pub fn is_aligned(&self, timestamp: i64) -> bool {
+        self.floor_timestamp(timestamp) == timestamp
+    }
+
+    /// Number of candles opening in `[from, to)`.
+    pub fn candles_between(&self, from: i64, to: i64) -> usize {
+        if to <= from {
+            return 0;
+        }
+        ((self.ceil_timestamp(to) - self.ceil_timestamp(from)) / self.as_millis()) as usize
+    }
+}
+
+/// Error returned when a timeframe string is not valid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTimeframeError(String);
+
+impl fmt::Display for ParseTimeframeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid timeframe {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseTimeframeError {}
+
+impl FromStr for Timeframe {
+    type Err = ParseTimeframeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseTimeframeError(s.to_string());
+        let split = s.len().checked_sub(1).ok_or_else(error)?;
+        let (count, unit) = s.split_at(split);
+        let unit = match unit {
+            "s" => TimeframeUnit::Second,
+            "m" => TimeframeUnit::Minute,
+            "h" | "H" => TimeframeUnit::Hour,
+            "d" | "D" => TimeframeUnit::Day,
+            "w" | "W" => TimeframeUnit::Week,
+            _ => return Err(error()),
+        };
+        match count.parse::<u32>() {
+            Ok(count) if count > 0 => Ok(Timeframe::new(count, unit)),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl fmt::Display for Timeframe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.count, self.unit.suffix())
+    }
+}
+
+impl Serialize for Timeframe {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timeframe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let tf: Timeframe = "4h".parse().unwrap();
+        assert_eq!(tf, Timeframe::hours(4));
+        assert_eq!(tf.as_seconds(), 14_400);
+        assert_eq!(tf.to_string(), "4h");
+        assert_eq!("1D".parse::<Timeframe>().unwrap(), Timeframe::days(1));
+        for invalid in ["", "h", "0m", "1M", "-1h", "1.5h"] {
+            assert!(invalid.parse::<Timeframe>().is_err(), "{}", invalid);
+        }
+        assert_eq!(
+            serde_json::from_str::<Timeframe>(r#""15m""#).unwrap(),
+            Timeframe::minutes(15)
+        );
+    }
+
+    #[test]
+    fn test_from_millis() {
+        assert_eq!(
+            Timeframe::from_millis(900_000),
+            Some(Timeframe::minutes(15))
+        );
+        assert_eq!(Timeframe::from_millis(7_200_000), Some(Timeframe::hours(2)));
+        assert_eq!(
+            Timeframe::from_millis(1_209_600_000),
+            Some(Timeframe::new(2, TimeframeUnit::Week))
+        );
+        assert_eq!(Timeframe::from_millis(1_500), None);
+    }
+
+    #[test]
+    fn test_alignment() {
+        let tf = Timeframe::hours(4);
+        // 2024-01-01 05:30 UTC
+        let ts = 1_704_087_000_000;
+        assert_eq!(tf.floor_timestamp(ts), 1_704_081_600_000);
+        assert_eq!(tf.ceil_timestamp(ts), 1_704_096_000_000);
+        assert!(tf.is_aligned(1_704_081_600_000));
+        assert_eq!(tf.floor_timestamp(-1), -14_400_000);
+        assert_eq!(tf.candles_between(1_704_081_600_000, 1_704_096_000_001), 2);
+        assert_eq!(tf.candles_between(ts, ts), 0);
+
+        // 2024-01-03 is a Wednesday, its week opened on Monday 2024-01-01
+        let week = Timeframe::new(1, TimeframeUnit::Week);
+        assert_eq!(week.floor_timestamp(1_704_283_200_000), 1_704_067_200_000);
+    }
+}
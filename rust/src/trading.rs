@@ -0,0 +1,415 @@
+//! Order execution through the platform.
+//!
+//! Orders are submitted to the host, which routes them to the exchange with the user's
+//! credentials, so functions never need direct access to exchange APIs. Every call takes
+//! the [`ExecutionMode`] of the invocation, from
+//! [`FunctionArgs::get_execution_mode`](crate::FunctionArgs::get_execution_mode), so in
+//! paper mode the host simulates the orders and reports the simulated account instead of
+//! the real one.
+//!
+//! ```ignore
+//! let ack = trading::place_order(args.get_execution_mode(), &order)?;
+//! ```
+use extism_pdk::{Error, Json, WithReturnCode};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::ExecutionMode;
+use crate::orders::OrderRequest;
+
+#[cfg(any(not(feature = "test-utils"), target_arch = "wasm32"))]
+mod host {
+    use super::{Balance, CancelRequest, ModifyRequest, OrderAck, OrderRequest, Position, Scoped};
+    use extism_pdk::{Json, host_fn};
+
+    #[host_fn]
+    extern "ExtismHost" {
+        pub fn place_order(request: Json<Scoped<OrderRequest>>) -> Json<OrderAck>;
+        pub fn cancel_order(request: Json<Scoped<CancelRequest>>) -> Json<OrderAck>;
+        pub fn modify_order(request: Json<Scoped<ModifyRequest>>) -> Json<OrderAck>;
+        pub fn get_open_positions(request: Json<Scoped<()>>) -> Json<Vec<Position>>;
+        pub fn get_balances(request: Json<Scoped<()>>) -> Json<Vec<Balance>>;
+    }
+}
+
+// Placed orders fill immediately unless the test queued another acknowledgement
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+mod host {
+    use super::{
+        Balance, CancelRequest, ModifyRequest, OrderAck, OrderRequest, OrderStatus, Position,
+        Scoped,
+    };
+    use crate::testing::{MockHost, host_call};
+    use extism_pdk::{Error, Json};
+
+    fn ack(host: &mut MockHost, order_id: String, status: OrderStatus) -> Json<OrderAck> {
+        Json(host.order_acks.pop_front().unwrap_or(OrderAck {
+            order_id,
+            client_order_id: None,
+            status,
+            filled_quantity: Default::default(),
+            average_price: None,
+            reason: None,
+        }))
+    }
+
+    pub unsafe fn place_order(
+        Json(scoped): Json<Scoped<OrderRequest>>,
+    ) -> Result<Json<OrderAck>, Error> {
+        host_call("place_order", |host| {
+            let order = scoped.request;
+            host.placed_orders.push((scoped.mode, order.clone()));
+            let Json(mut ack) = ack(
+                host,
+                format!("mock-{}", host.placed_orders.len()),
+                OrderStatus::Filled,
+            );
+            if ack.status == OrderStatus::Filled && ack.filled_quantity.is_zero() {
+                ack.filled_quantity = order.quantity;
+            }
+            ack.client_order_id = ack.client_order_id.or(order.client_order_id);
+            Ok(Json(ack))
+        })
+    }
+
+    pub unsafe fn cancel_order(
+        Json(scoped): Json<Scoped<CancelRequest>>,
+    ) -> Result<Json<OrderAck>, Error> {
+        host_call("cancel_order", |host| {
+            let order_id = scoped.request.order_id;
+            host.canceled_orders.push((scoped.mode, order_id.clone()));
+            Ok(ack(host, order_id, OrderStatus::Canceled))
+        })
+    }
+
+    pub unsafe fn modify_order(
+        Json(scoped): Json<Scoped<ModifyRequest>>,
+    ) -> Result<Json<OrderAck>, Error> {
+        host_call("modify_order", |host| {
+            let ModifyRequest { order_id, changes } = scoped.request;
+            host.modified_orders
+                .push((scoped.mode, order_id.clone(), changes));
+            Ok(ack(host, order_id, OrderStatus::New))
+        })
+    }
+
+    pub unsafe fn get_open_positions(_: Json<Scoped<()>>) -> Result<Json<Vec<Position>>, Error> {
+        host_call("get_open_positions", |host| {
+            Ok(Json(host.positions.clone()))
+        })
+    }
+
+    pub unsafe fn get_balances(_: Json<Scoped<()>>) -> Result<Json<Vec<Balance>>, Error> {
+        host_call("get_balances", |host| Ok(Json(host.balances.clone())))
+    }
+}
+
+// A host call payload tagged with the execution mode of the invocation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Scoped<T> {
+    mode: ExecutionMode,
+    #[serde(flatten)]
+    request: T,
+}
+
+fn scoped<T>(mode: ExecutionMode, request: T) -> Json<Scoped<T>> {
+    Json(Scoped { mode, request })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+    New,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Rejected,
+    /// The order id is not known to the exchange.
+    NotFound,
+}
+
+/// The host acknowledgement of a submitted order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderAck {
+    /// Identifier assigned by the exchange.
+    pub order_id: String,
+    #[serde(default)]
+    pub client_order_id: Option<String>,
+    pub status: OrderStatus,
+    #[serde(default)]
+    pub filled_quantity: Decimal,
+    #[serde(default)]
+    pub average_price: Option<Decimal>,
+    /// Explanation given by the exchange when the order is rejected.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Changes to apply to an open order with [`modify_order`]. Fields left to `None` are kept.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderChanges {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CancelRequest {
+    order_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ModifyRequest {
+    order_id: String,
+    #[serde(flatten)]
+    changes: OrderChanges,
+}
+
+/// Submits an order and waits for the exchange acknowledgement.
+///
+/// Fails with return code 11 if the host cannot be reached and 12 if the exchange
+/// rejects the order.
+pub fn place_order(
+    mode: ExecutionMode,
+    request: &OrderRequest,
+) -> Result<OrderAck, WithReturnCode<Error>> {
+    let ack = unsafe { host::place_order(scoped(mode, request.clone())) };
+    check_ack(ack, "place")
+}
+
+/// Cancels an open order.
+///
+/// Fails with return code 11 if the host cannot be reached, 12 if the exchange rejects
+/// the cancellation and 13 if the order is unknown.
+pub fn cancel_order(
+    mode: ExecutionMode,
+    order_id: &str,
+) -> Result<OrderAck, WithReturnCode<Error>> {
+    let request = CancelRequest {
+        order_id: order_id.to_string(),
+    };
+    let ack = unsafe { host::cancel_order(scoped(mode, request)) };
+    check_ack(ack, "cancel")
+}
+
+/// Changes the quantity and/or price of an open order.
+///
+/// Fails with the same return codes as [`cancel_order`].
+pub fn modify_order(
+    mode: ExecutionMode,
+    order_id: &str,
+    changes: &OrderChanges,
+) -> Result<OrderAck, WithReturnCode<Error>> {
+    let request = ModifyRequest {
+        order_id: order_id.to_string(),
+        changes: changes.clone(),
+    };
+    let ack = unsafe { host::modify_order(scoped(mode, request)) };
+    check_ack(ack, "modify")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PositionSide {
+    Long,
+    Short,
+}
+
+/// An open position of the account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub symbol: String,
+    pub exchange: String,
+    pub side: PositionSide,
+    /// Absolute size of the position.
+    pub size: Decimal,
+    pub entry_price: Decimal,
+    pub unrealized_pnl: Decimal,
+}
+
+/// Returns the open positions of the account.
+///
+/// Fails with return code 14 if the host cannot provide them.
+pub fn get_open_positions(mode: ExecutionMode) -> Result<Vec<Position>, WithReturnCode<Error>> {
+    account_query(
+        unsafe { host::get_open_positions(scoped(mode, ())) },
+        "open positions",
+    )
+}
+
+/// Returns the open position on a symbol, if any.
+pub fn get_open_position(
+    mode: ExecutionMode,
+    symbol: &str,
+    exchange: &str,
+) -> Result<Option<Position>, WithReturnCode<Error>> {
+    Ok(get_open_positions(mode)?
+        .into_iter()
+        .find(|p| p.symbol == symbol && p.exchange == exchange))
+}
+
+/// Funds of the account in one currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Balance {
+    pub currency: String,
+    /// Funds available for new orders.
+    pub free: Decimal,
+    /// Funds reserved by open orders or positions.
+    pub locked: Decimal,
+}
+
+impl Balance {
+    pub fn total(&self) -> Decimal {
+        self.free + self.locked
+    }
+}
+
+/// Returns the balances of the account, one per currency.
+///
+/// Fails with return code 14 if the host cannot provide them.
+pub fn get_balances(mode: ExecutionMode) -> Result<Vec<Balance>, WithReturnCode<Error>> {
+    account_query(unsafe { host::get_balances(scoped(mode, ())) }, "balances")
+}
+
+/// Returns the balance of one currency, zero if the account holds none.
+pub fn get_balance(mode: ExecutionMode, currency: &str) -> Result<Balance, WithReturnCode<Error>> {
+    Ok(get_balances(mode)?
+        .into_iter()
+        .find(|b| b.currency == currency)
+        .unwrap_or(Balance {
+            currency: currency.to_string(),
+            free: Decimal::ZERO,
+            locked: Decimal::ZERO,
+        }))
+}
+
+fn account_query<T>(res: Result<Json<T>, Error>, what: &str) -> Result<T, WithReturnCode<Error>> {
+    res.map(|Json(value)| value).map_err(|e| {
+        WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "impossible to get {}: {}",
+                what, e
+            ))),
+            14,
+        )
+    })
+}
+
+fn check_ack(
+    ack: Result<Json<OrderAck>, Error>,
+    action: &str,
+) -> Result<OrderAck, WithReturnCode<Error>> {
+    let Json(ack) = ack.map_err(|e| {
+        WithReturnCode::new(
+            Error::new(std::io::Error::other(format!(
+                "impossible to {} order: {}",
+                action, e
+            ))),
+            11,
+        )
+    })?;
+    let code = match ack.status {
+        OrderStatus::Rejected => 12,
+        OrderStatus::NotFound => 13,
+        _ => return Ok(ack),
+    };
+    Err(WithReturnCode::new(
+        Error::new(std::io::Error::other(format!(
+            "Order {} {}: {}",
+            ack.order_id,
+            if code == 12 { "rejected" } else { "not found" },
+            ack.reason.as_deref().unwrap_or("no reason given")
+        ))),
+        code,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rejected_ack() {
+        let ack: OrderAck = serde_json::from_value(json!({
+            "order_id": "1",
+            "status": "rejected",
+            "reason": "insufficient balance",
+        }))
+        .unwrap();
+        let err = check_ack(Ok(Json(ack.clone())), "place").unwrap_err();
+        assert_eq!(err.1, 12);
+        let filled = OrderAck {
+            status: OrderStatus::Filled,
+            ..ack.clone()
+        };
+        assert!(check_ack(Ok(Json(filled)), "place").is_ok());
+        let unknown = OrderAck {
+            status: OrderStatus::NotFound,
+            ..ack
+        };
+        assert_eq!(check_ack(Ok(Json(unknown)), "cancel").unwrap_err().1, 13);
+    }
+
+    #[test]
+    fn test_position_deserialization() {
+        let position: Position = serde_json::from_value(json!({
+            "symbol": "ETHUSDT",
+            "exchange": "binance",
+            "side": "short",
+            "size": "1.5",
+            "entry_price": 3000,
+            "unrealized_pnl": "-12.5",
+        }))
+        .unwrap();
+        assert_eq!(position.side, PositionSide::Short);
+        assert_eq!(position.size, Decimal::new(15, 1));
+        assert_eq!(position.unrealized_pnl, Decimal::new(-125, 1));
+    }
+
+    #[test]
+    fn test_balance_total() {
+        let balance: Balance =
+            serde_json::from_value(json!({"currency": "USDT", "free": "100.5", "locked": 20}))
+                .unwrap();
+        assert_eq!(balance.total(), Decimal::new(1205, 1));
+    }
+
+    #[test]
+    fn test_scoped_payload() {
+        let payload = Scoped {
+            mode: ExecutionMode::Paper,
+            request: CancelRequest {
+                order_id: "7".to_string(),
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            json!({"mode": "paper", "order_id": "7"})
+        );
+        let payload = Scoped {
+            mode: ExecutionMode::Live,
+            request: (),
+        };
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            json!({"mode": "live"})
+        );
+    }
+
+    #[test]
+    fn test_modify_request_serialization() {
+        let request = ModifyRequest {
+            order_id: "42".to_string(),
+            changes: OrderChanges {
+                price: Some(Decimal::new(101, 0)),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({"order_id": "42", "price": "101"})
+        );
+    }
+}